@@ -0,0 +1,63 @@
+//! Conversions between an enum and the field of each of its single-field tuple variants.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DataEnum, DeriveInput, Fields};
+
+pub fn derive(input: &DeriveInput, data: &DataEnum) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut impls = TokenStream::new();
+
+    for variant in &data.variants {
+        let ty = match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0].ty,
+            _ => continue,
+        };
+
+        let ident = &variant.ident;
+
+        impls.extend(quote! {
+            impl #impl_generics ::core::convert::From<#ty> for #name #ty_generics #where_clause {
+                fn from(value: #ty) -> Self {
+                    Self::#ident(value)
+                }
+            }
+
+            impl #impl_generics ::safecast::AsType<#ty> for #name #ty_generics #where_clause {
+                #[allow(unreachable_patterns)]
+                fn as_type(&self) -> Option<&#ty> {
+                    match self {
+                        Self::#ident(value) => Some(value),
+                        _ => None,
+                    }
+                }
+
+                #[allow(unreachable_patterns)]
+                fn as_type_mut(&mut self) -> Option<&mut #ty> {
+                    match self {
+                        Self::#ident(value) => Some(value),
+                        _ => None,
+                    }
+                }
+
+                #[allow(unreachable_patterns)]
+                fn into_type(self) -> Option<#ty> {
+                    match self {
+                        Self::#ident(value) => Some(value),
+                        _ => None,
+                    }
+                }
+            }
+        });
+    }
+
+    if impls.is_empty() {
+        Err(syn::Error::new_spanned(
+            name,
+            "AsType requires a variant with exactly one unnamed field",
+        ))
+    } else {
+        Ok(impls)
+    }
+}
@@ -0,0 +1,111 @@
+//! Parsing of `#[cast(...)]` attributes.
+
+use syn::punctuated::Punctuated;
+use syn::{Attribute, LitStr, Member, Token, Type, WherePredicate};
+
+/// The casts requested by the `#[cast(...)]` attributes of a type.
+#[derive(Default)]
+pub struct Container {
+    /// Types to implement `CastFrom` from.
+    pub from: Vec<Type>,
+    /// Types to implement `TryCastFrom` from.
+    pub try_from: Vec<Type>,
+    /// Types to implement `CastFrom<Self>` for.
+    pub into: Vec<Type>,
+    /// Also implement `TryCastFromFields` for each `try_from` type.
+    pub aggregate_errors: bool,
+    /// Additional where clause predicates for each implementation.
+    pub bound: Vec<WherePredicate>,
+}
+
+impl Container {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut container = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("cast")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("aggregate_errors") {
+                    container.aggregate_errors = true;
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("bound") {
+                    let bound: LitStr = meta.value()?.parse()?;
+                    let bound = bound
+                        .parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+
+                    container.bound.extend(bound);
+                    return Ok(());
+                }
+
+                let list = if meta.path.is_ident("from") {
+                    &mut container.from
+                } else if meta.path.is_ident("try_from") {
+                    &mut container.try_from
+                } else if meta.path.is_ident("into") {
+                    &mut container.into
+                } else {
+                    return Err(meta.error(
+                        "expected `from`, `try_from`, `into`, `aggregate_errors`, or `bound`",
+                    ));
+                };
+
+                let ty: LitStr = meta.value()?.parse()?;
+                list.push(ty.parse()?);
+                Ok(())
+            })?;
+        }
+
+        Ok(container)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.from.is_empty() && self.try_from.is_empty() && self.into.is_empty()
+    }
+}
+
+/// The options given by the `#[cast(...)]` attributes of a struct field.
+#[derive(Default)]
+pub struct Field {
+    /// Cast this field from the whole source value, rather than from one of its fields.
+    pub flatten: bool,
+    /// The path of the source field to cast this field from, if not the field of the same name.
+    pub from_field: Option<Vec<Member>>,
+}
+
+impl Field {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut field = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("cast")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("flatten") {
+                    field.flatten = true;
+                    Ok(())
+                } else if meta.path.is_ident("from_field") {
+                    let path: LitStr = meta.value()?.parse()?;
+                    let members = path
+                        .value()
+                        .split('.')
+                        .map(syn::parse_str)
+                        .collect::<syn::Result<_>>()
+                        .map_err(|_| meta.error("expected a path like \"inner.id\""))?;
+
+                    field.from_field = Some(members);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `flatten` or `from_field`"))
+                }
+            })?;
+        }
+
+        if field.flatten && field.from_field.is_some() {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "a field can't be both `flatten` and `from_field`",
+            ));
+        }
+
+        Ok(field)
+    }
+}
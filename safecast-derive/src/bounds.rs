@@ -0,0 +1,121 @@
+//! Where clause bounds inferred for the fields of a generic type.
+
+use proc_macro2::{Group, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{DeriveInput, GenericArgument, Ident, PathArguments, Type, WherePredicate};
+
+/// The type parameters of a type, and the type arguments of the other type in a cast which
+/// correspond to them, in order.
+pub struct Params<'a> {
+    params: Vec<&'a Ident>,
+    args: Option<Vec<Type>>,
+}
+
+impl<'a> Params<'a> {
+    pub fn new(input: &'a DeriveInput, other: &Type) -> Self {
+        let params: Vec<&Ident> = input
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .collect();
+
+        let args = type_args(other).filter(|args| args.len() == params.len());
+
+        Self { params, args }
+    }
+
+    /// Returns `true` if `ty` mentions any of these type parameters.
+    pub fn mentioned_by(&self, ty: &Type) -> bool {
+        mentions(&self.params, ty.to_token_stream())
+    }
+
+    /// The type of the field of the other type which corresponds to a field of type `ty`, if `ty`
+    /// mentions a type parameter, found by substituting the other type's arguments.
+    pub fn counterpart(&self, ty: &Type) -> Option<Type> {
+        let args = self.args.as_ref()?;
+
+        if self.mentioned_by(ty) {
+            syn::parse2(substitute(&self.params, args, ty.to_token_stream())).ok()
+        } else {
+            None
+        }
+    }
+}
+
+/// The where clause predicates of one implementation, without duplicates.
+#[derive(Default)]
+pub struct Bounds {
+    predicates: Vec<WherePredicate>,
+}
+
+impl Bounds {
+    pub fn push(&mut self, predicate: WherePredicate) {
+        let tokens = predicate.to_token_stream().to_string();
+
+        if !self
+            .predicates
+            .iter()
+            .any(|existing| existing.to_token_stream().to_string() == tokens)
+        {
+            self.predicates.push(predicate);
+        }
+    }
+
+    /// Bound `ty` by `CastFrom<source>` (or another cast trait), unless they are the same type.
+    pub fn cast(&mut self, ty: &Type, source: &Type, cast: TokenStream) {
+        if ty.to_token_stream().to_string() != source.to_token_stream().to_string() {
+            self.push(syn::parse_quote!(#ty: ::safecast::#cast<#source>));
+        }
+    }
+
+    pub fn into_predicates(self) -> Vec<WherePredicate> {
+        self.predicates
+    }
+}
+
+/// The type arguments of the last segment of the path of `ty`, which may be a reference.
+fn type_args(ty: &Type) -> Option<Vec<Type>> {
+    match ty {
+        Type::Reference(reference) => type_args(&reference.elem),
+        Type::Path(ty) if ty.qself.is_none() => match &ty.path.segments.last()?.arguments {
+            PathArguments::AngleBracketed(args) => Some(
+                args.args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        GenericArgument::Type(ty) => Some(ty.clone()),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            _ => Some(Vec::new()),
+        },
+        _ => None,
+    }
+}
+
+fn mentions(params: &[&Ident], tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => params.iter().any(|param| **param == ident),
+        TokenTree::Group(group) => mentions(params, group.stream()),
+        _ => false,
+    })
+}
+
+fn substitute(params: &[&Ident], args: &[Type], tokens: TokenStream) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(ident) => match params.iter().position(|param| **param == ident) {
+                Some(i) => args[i].to_token_stream(),
+                None => TokenTree::Ident(ident).into(),
+            },
+            TokenTree::Group(group) => {
+                let stream = substitute(params, args, group.stream());
+                let mut substituted = Group::new(group.delimiter(), stream);
+                substituted.set_span(group.span());
+                TokenTree::Group(substituted).into()
+            }
+            token => token.into(),
+        })
+        .collect()
+}
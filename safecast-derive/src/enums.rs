@@ -0,0 +1,203 @@
+//! Casts between enums which share some or all of their variants.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DataEnum, DeriveInput, Fields, Ident, Type};
+
+use crate::attr::Container;
+use crate::bounds::{Bounds, Params};
+use crate::{impl_generics, type_path};
+
+pub fn derive(
+    input: &DeriveInput,
+    data: &DataEnum,
+    container: &Container,
+) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut impls = TokenStream::new();
+
+    if container.aggregate_errors {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`aggregate_errors` is only supported for structs",
+        ));
+    }
+
+    for source in &container.from {
+        let bounds = infer_bounds(input, data, source, |ty, other, bounds| {
+            bounds.cast(ty, other, quote!(CastFrom))
+        });
+
+        let generics = impl_generics(input, container, None, bounds);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let source_path = type_path(source)?;
+        let arms = data.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let (pattern, bindings) = destructure(&variant.fields);
+            let fields = construct(
+                &variant.fields,
+                bindings
+                    .iter()
+                    .zip(&variant.fields)
+                    .map(|(binding, field)| {
+                        let ty = &field.ty;
+                        quote!(<#ty as ::safecast::CastFrom<_>>::cast_from(#binding))
+                    }),
+            );
+
+            quote!(#source_path::#ident #pattern => Self::#ident #fields,)
+        });
+
+        impls.extend(quote! {
+            impl #impl_generics ::safecast::CastFrom<#source> for #name #ty_generics
+            #where_clause
+            {
+                fn cast_from(value: #source) -> Self {
+                    match value {
+                        #(#arms)*
+                    }
+                }
+            }
+        });
+    }
+
+    for source in &container.try_from {
+        let bounds = infer_bounds(input, data, source, |ty, other, bounds| {
+            bounds.cast(ty, other, quote!(TryCastFrom))
+        });
+
+        let generics = impl_generics(input, container, None, bounds);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let source_path = type_path(source)?;
+
+        let can_cast = data.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let (pattern, bindings) = destructure(&variant.fields);
+            let checks = bindings
+                .iter()
+                .zip(&variant.fields)
+                .map(|(binding, field)| {
+                    let ty = &field.ty;
+                    quote!(&& <#ty as ::safecast::TryCastFrom<_>>::can_cast_from(#binding))
+                });
+
+            quote!(#source_path::#ident #pattern => true #(#checks)*,)
+        });
+
+        let opt_cast = data.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let (pattern, bindings) = destructure(&variant.fields);
+            let fields = construct(
+                &variant.fields,
+                bindings
+                    .iter()
+                    .zip(&variant.fields)
+                    .map(|(binding, field)| {
+                        let ty = &field.ty;
+                        quote!(<#ty as ::safecast::TryCastFrom<_>>::opt_cast_from(#binding)?)
+                    }),
+            );
+
+            quote!(#source_path::#ident #pattern => Some(Self::#ident #fields),)
+        });
+
+        impls.extend(quote! {
+            impl #impl_generics ::safecast::TryCastFrom<#source> for #name #ty_generics
+            #where_clause
+            {
+                #[allow(unreachable_patterns)]
+                fn can_cast_from(value: &#source) -> bool {
+                    match value {
+                        #(#can_cast)*
+                        _ => false,
+                    }
+                }
+
+                #[allow(unreachable_patterns)]
+                fn opt_cast_from(value: #source) -> Option<Self> {
+                    match value {
+                        #(#opt_cast)*
+                        _ => None,
+                    }
+                }
+            }
+        });
+    }
+
+    for target in &container.into {
+        let bounds = infer_bounds(input, data, target, |ty, other, bounds| {
+            bounds.cast(other, ty, quote!(CastFrom))
+        });
+
+        let generics = impl_generics(input, container, None, bounds);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let target_path = type_path(target)?;
+        let arms = data.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let (pattern, bindings) = destructure(&variant.fields);
+            let fields = construct(
+                &variant.fields,
+                bindings
+                    .iter()
+                    .map(|binding| quote!(::safecast::CastInto::cast_into(#binding))),
+            );
+
+            quote!(#name::#ident #pattern => #target_path::#ident #fields,)
+        });
+
+        impls.extend(quote! {
+            impl #impl_generics ::safecast::CastFrom<#name #ty_generics> for #target
+            #where_clause
+            {
+                fn cast_from(value: #name #ty_generics) -> Self {
+                    match value {
+                        #(#arms)*
+                    }
+                }
+            }
+        });
+    }
+
+    Ok(impls)
+}
+
+/// The bounds inferred by calling `bound` with the type of each field whose type mentions a type
+/// parameter, and the type of the corresponding field of the variant of `other`.
+fn infer_bounds<B>(input: &DeriveInput, data: &DataEnum, other: &Type, bound: B) -> Bounds
+where
+    B: Fn(&Type, &Type, &mut Bounds),
+{
+    let params = Params::new(input, other);
+    let mut bounds = Bounds::default();
+
+    for field in data.variants.iter().flat_map(|variant| &variant.fields) {
+        if let Some(counterpart) = params.counterpart(&field.ty) {
+            bound(&field.ty, &counterpart, &mut bounds);
+        }
+    }
+
+    bounds
+}
+
+/// A pattern to destructure the fields of a variant, and the names of its bindings.
+fn destructure(fields: &Fields) -> (TokenStream, Vec<Ident>) {
+    let bindings: Vec<Ident> = (0..fields.len())
+        .map(|i| format_ident!("__field{}", i))
+        .collect();
+
+    let pattern = construct(fields, bindings.iter().map(|binding| quote!(#binding)));
+    (pattern, bindings)
+}
+
+/// The fields of a variant pattern or expression, given the tokens for each field.
+fn construct<I: Iterator<Item = TokenStream>>(fields: &Fields, values: I) -> TokenStream {
+    match fields {
+        Fields::Unit => TokenStream::new(),
+        Fields::Unnamed(_) => quote!((#(#values),*)),
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|field| &field.ident);
+            quote!({ #(#names: #values),* })
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Casts between an integer bitmask and a set of fieldless enum variants.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DataEnum, DeriveInput, Fields, Ident};
+
+const REPRS: [&str; 6] = ["u8", "u16", "u32", "u64", "u128", "usize"];
+
+pub fn derive(input: &DeriveInput, data: &DataEnum) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let bits = repr(input)?;
+
+    if let Some(variant) = data
+        .variants
+        .iter()
+        .find(|variant| !matches!(variant.fields, Fields::Unit))
+    {
+        return Err(syn::Error::new_spanned(
+            variant,
+            "Flag can only be derived for an enum with no fields",
+        ));
+    }
+
+    let variants: Vec<&Ident> = data.variants.iter().map(|variant| &variant.ident).collect();
+    let messages = variants.iter().map(|variant| {
+        format!(
+            "the discriminant of {}::{} is not a power of two",
+            name, variant
+        )
+    });
+
+    Ok(quote! {
+        impl ::safecast::Flag for #name {
+            type Bits = #bits;
+
+            const FLAGS: &'static [Self] = &[#(Self::#variants),*];
+
+            fn bit(self) -> #bits {
+                self as #bits
+            }
+        }
+
+        const _: () = {
+            #(::core::assert!((#name::#variants as #bits).is_power_of_two(), #messages);)*
+        };
+    })
+}
+
+/// The unsigned integer type named in the `#[repr(...)]` attribute of the enum.
+fn repr(input: &DeriveInput) -> syn::Result<Ident> {
+    let mut bits = None;
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                if REPRS.iter().any(|repr| ident == repr) {
+                    bits = Some(ident.clone());
+                }
+            }
+
+            Ok(())
+        })?;
+    }
+
+    bits.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "Flag requires an attribute like #[repr(u32)] naming an unsigned integer type",
+        )
+    })
+}
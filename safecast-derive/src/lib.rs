@@ -0,0 +1,369 @@
+//! Derive macros for [`safecast`](https://docs.rs/safecast).
+//! Use these through the `derive` feature of `safecast`, which re-exports them.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use syn::{
+    parse_macro_input, Data, DeriveInput, GenericParam, Generics, Lifetime, LifetimeParam, Path,
+    PathArguments, Type,
+};
+
+mod as_type;
+mod attr;
+mod bounds;
+mod enums;
+mod flags;
+mod structs;
+mod variant;
+
+/// Derive casts between this type and the types named in its `#[cast(...)]` attributes.
+///
+/// For an enum, each variant is matched by name with the variant of the same name in the other
+/// enum, and each field of the variant is cast into the corresponding field of the other.
+///  - `#[cast(from = "Other")]` implements `CastFrom<Other>`; every variant of `Other` must have
+///    a counterpart in this enum.
+///  - `#[cast(try_from = "Other")]` implements `TryCastFrom<Other>`, which succeeds only for the
+///    variants of `Other` which have a counterpart in this enum, and whose fields can be cast.
+///  - `#[cast(into = "Other")]` implements `CastFrom<Self>` for `Other`; every variant of this
+///    enum must have a counterpart in `Other`.
+///
+/// For a struct, each field is cast from the field with the same name (or index) in the other
+/// struct. The `from` and `try_from` attributes accept a reference type like `"&Other"`, in which
+/// case each source field is cloned before it's cast. Fields accept these attributes:
+///  - `#[cast(from_field = "inner.id")]` casts the field from a nested field of the source, so
+///    that a flat struct can be cast from a nested one.
+///  - `#[cast(flatten)]` casts the field from a reference to the whole source value, so that a
+///    nested struct can be cast from a flat one; the field's type must implement
+///    `CastFrom<&Other>` (or `TryCastFrom<&Other>`), which can itself be derived.
+///
+/// `into` is only supported for structs with neither kind of field attribute.
+///
+/// A struct with `#[cast(aggregate_errors)]` also implements `TryCastFromFields` for each
+/// `try_from` type, to report every field which can't be cast (described by the `Debug`
+/// representation of its source value) instead of only the first, so the source of each field
+/// which is not flattened must implement `Debug`. The type of a flattened field must also
+/// implement `TryCastFromFields`, and its errors are reported with a nested path like
+/// `"contact.email"`; a flattened field is only cast once.
+///
+/// Each attribute may be repeated to implement casts to or from several types.
+///
+/// The type may be generic, in which case its parameters and where clause are carried over to
+/// each implementation, and the other types may refer to its parameters, as in
+/// `#[cast(from = "Other<T>")]`. Each implementation is also bounded for each field whose type
+/// mentions a type parameter. The field types of the other type aren't visible to the derive, so
+/// it assumes that the type arguments of the other type correspond to the type parameters of
+/// this one, in order: a field of type `T` in an enum `Maybe<T>` with
+/// `#[cast(try_from = "Option<u8>")]` is bounded by `T: TryCastFrom<u8>`. When the other type
+/// has no such arguments, or the inferred bounds are wrong, replace them with your own, as in
+/// `#[cast(bound = "T: CastFrom<u64>")]`.
+///
+/// Example:
+/// ```
+/// use safecast::{Cast, CastFrom, TryCastFrom};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Internal {
+///     Int(i64),
+///     Text(String),
+///     Pair { left: i64, right: i64 },
+///     Secret(Vec<u8>),
+/// }
+///
+/// #[derive(Cast, Debug, PartialEq)]
+/// #[cast(try_from = "Internal", into = "Internal")]
+/// enum Public {
+///     Int(i64),
+///     Text(String),
+///     Pair { left: i64, right: i64 },
+/// }
+///
+/// assert_eq!(Public::opt_cast_from(Internal::Int(1)), Some(Public::Int(1)));
+/// assert!(!Public::can_cast_from(&Internal::Secret(vec![])));
+///
+/// let pair = Public::Pair { left: 1, right: 2 };
+/// assert_eq!(Internal::cast_from(pair), Internal::Pair { left: 1, right: 2 });
+/// ```
+///
+/// Casting between a nested struct and a flat one:
+/// ```
+/// use safecast::{Cast, CastFrom};
+///
+/// #[derive(Clone)]
+/// struct Profile {
+///     name: String,
+///     email: String,
+/// }
+///
+/// struct User {
+///     id: u32,
+///     profile: Profile,
+/// }
+///
+/// #[derive(Cast)]
+/// #[cast(from = "User")]
+/// struct UserDto {
+///     id: u64,
+///     #[cast(from_field = "profile.name")]
+///     name: String,
+///     #[cast(from_field = "profile.email")]
+///     email: String,
+/// }
+///
+/// #[derive(Cast)]
+/// #[cast(from = "&UserDto")]
+/// struct Contact {
+///     name: String,
+///     email: String,
+/// }
+///
+/// #[derive(Cast)]
+/// #[cast(from = "UserDto")]
+/// struct Account {
+///     id: u64,
+///     #[cast(flatten)]
+///     contact: Contact,
+/// }
+///
+/// let profile = Profile { name: "Ada".into(), email: "ada@example.com".into() };
+/// let dto = UserDto::cast_from(User { id: 1, profile });
+/// assert_eq!(dto.name, "Ada");
+///
+/// let account = Account::cast_from(dto);
+/// assert_eq!(account.contact.email, "ada@example.com");
+/// ```
+///
+/// A generic type:
+/// ```
+/// use safecast::{Cast, CastFrom, TryCastFrom};
+///
+/// struct Raw<T> {
+///     id: u32,
+///     items: Vec<T>,
+/// }
+///
+/// #[derive(Cast)]
+/// #[cast(from = "Raw<T>", try_from = "&Raw<T>")]
+/// struct Record<T> {
+///     id: u64,
+///     items: Vec<T>,
+/// }
+///
+/// #[derive(Cast, Debug, PartialEq)]
+/// #[cast(try_from = "Option<u8>", into = "Option<u64>")]
+/// enum Maybe<T> {
+///     Some(T),
+///     None,
+/// }
+///
+/// let raw = Raw { id: 1, items: vec!["a"] };
+/// assert_eq!(Record::opt_cast_from(&raw).map(|record| record.items), Some(vec!["a"]));
+///
+/// let record = Record::cast_from(raw);
+/// assert_eq!(record.id, 1);
+///
+/// let maybe = Maybe::<u32>::opt_cast_from(Some(1u8)).unwrap();
+/// assert_eq!(maybe, Maybe::Some(1));
+/// assert_eq!(Option::<u64>::cast_from(maybe), Some(1));
+/// ```
+#[proc_macro_derive(Cast, attributes(cast))]
+pub fn derive_cast(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `Flag` for a fieldless enum with a `#[repr]` attribute naming an unsigned integer type,
+/// so that a set of its variants can be cast to and from an integer bitmask using `Flags`.
+///
+/// Each discriminant must be a distinct power of two; this is checked at compile time.
+///
+/// Example:
+/// ```
+/// use std::collections::HashSet;
+/// use safecast::{CastFrom, Flag, Flags, TryCastFrom};
+///
+/// #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Flag)]
+/// #[repr(u32)]
+/// enum Permission {
+///     Read = 1,
+///     Write = 2,
+///     Execute = 4,
+/// }
+///
+/// let flags = Flags::from(HashSet::from([Permission::Read, Permission::Execute]));
+/// assert_eq!(u32::cast_from(flags), 5);
+///
+/// let flags = Flags::<Permission>::opt_cast_from(3u32).unwrap();
+/// assert!(flags.contains(&Permission::Write));
+/// assert!(Flags::<Permission>::opt_cast_from(8u32).is_none());
+/// ```
+#[proc_macro_derive(Flag)]
+pub fn derive_flag(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let derived = match &input.data {
+        Data::Enum(data) if input.generics.params.is_empty() => flags::derive(&input, data),
+        Data::Enum(_) => Err(syn::Error::new_spanned(
+            &input.generics,
+            "Flag can't be derived for a generic type",
+        )),
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            "Flag can only be derived for an enum",
+        )),
+    };
+
+    derived
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `From` and `AsType` for an enum, for the field type of each variant with exactly one
+/// unnamed field, like the `as_type!` macro. The enum may be generic, but no two of these
+/// variants may have field types which could be the same, like `T` and `String`.
+///
+/// Example:
+/// ```
+/// use safecast::{AsType, CastInto};
+///
+/// #[derive(AsType, Debug, PartialEq)]
+/// enum Value<T> {
+///     Scalar(T),
+///     List(Vec<T>),
+///     Nil,
+/// }
+///
+/// let value: Value<u8> = vec![1, 2].cast_into();
+/// assert_eq!(AsType::<Vec<u8>>::as_type(&value), Some(&vec![1, 2]));
+/// assert_eq!(AsType::<u8>::into_type(value), None);
+///
+/// let mut value = Value::Nil;
+/// *value.get_or_insert_type(0u8) += 1;
+/// assert_eq!(value, Value::Scalar(1));
+/// ```
+#[proc_macro_derive(AsType)]
+pub fn derive_as_type(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let derived = match &input.data {
+        Data::Enum(data) => as_type::derive(&input, data),
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            "AsType can only be derived for an enum",
+        )),
+    };
+
+    derived
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `VariantName` for an enum, to report the name of the variant of each instance.
+///
+/// Example:
+/// ```
+/// use safecast::{CastError, VariantName};
+///
+/// #[derive(VariantName)]
+/// enum Value<T> {
+///     Scalar(T),
+///     List(Vec<T>),
+///     Nil,
+/// }
+///
+/// assert_eq!(Value::<u8>::Nil.variant_name(), "Nil");
+///
+/// let err = CastError::of_variant::<_, u8>(&Value::List(vec![1u8]));
+/// assert_eq!(err.source_variant(), Some("List"));
+/// ```
+#[proc_macro_derive(VariantName)]
+pub fn derive_variant_name(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let derived = match &input.data {
+        Data::Enum(data) => Ok(variant::derive(&input, data)),
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            "VariantName can only be derived for an enum",
+        )),
+    };
+
+    derived
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn derive(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let container = attr::Container::parse(&input.attrs)?;
+    if container.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "expected an attribute like #[cast(from = \"Type\")]",
+        ));
+    }
+
+    match &input.data {
+        Data::Enum(data) => enums::derive(input, data, &container),
+        Data::Struct(data) => structs::derive(input, data, &container),
+        Data::Union(_) => Err(syn::Error::new_spanned(
+            &input.ident,
+            "Cast can't be derived for a union",
+        )),
+    }
+}
+
+/// The generics of a cast implementation for `input`, declaring `lifetime` if given (and not
+/// already declared or `'static`), with the inferred `bounds` added to the where clause, or
+/// the `bound`s of its `#[cast(...)]` attributes instead if there are any.
+fn impl_generics(
+    input: &DeriveInput,
+    container: &attr::Container,
+    lifetime: Option<&Lifetime>,
+    bounds: bounds::Bounds,
+) -> Generics {
+    let mut generics = input.generics.clone();
+
+    let declared = |lifetime: &Lifetime| {
+        lifetime.ident == "static"
+            || generics
+                .lifetimes()
+                .any(|param| param.lifetime == *lifetime)
+    };
+
+    if let Some(lifetime) = lifetime.filter(|lifetime| !declared(lifetime)) {
+        let param = LifetimeParam::new(lifetime.clone());
+        generics.params.insert(0, GenericParam::Lifetime(param));
+    }
+
+    let predicates = &mut generics.make_where_clause().predicates;
+
+    if container.bound.is_empty() {
+        predicates.extend(bounds.into_predicates());
+    } else {
+        predicates.extend(container.bound.iter().cloned());
+    }
+
+    generics
+}
+
+/// The path of a type, in a form which can prefix a variant or fields in a pattern or expression.
+fn type_path(ty: &Type) -> syn::Result<Path> {
+    match ty {
+        Type::Path(ty) if ty.qself.is_none() => {
+            let mut path = ty.path.clone();
+
+            for segment in &mut path.segments {
+                if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                    args.colon2_token = Some(Default::default());
+                }
+            }
+
+            Ok(path)
+        }
+        _ => Err(syn::Error::new(
+            Span::call_site(),
+            "expected the path of a type",
+        )),
+    }
+}
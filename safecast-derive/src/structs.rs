@@ -0,0 +1,392 @@
+//! Casts between structs, field by field.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_quote, DataStruct, DeriveInput, Lifetime, Member, Type};
+
+use crate::attr::{self, Container};
+use crate::bounds::{Bounds, Params};
+use crate::{impl_generics, type_path};
+
+struct Field<'a> {
+    member: Member,
+    ty: &'a Type,
+    attr: attr::Field,
+}
+
+impl Field<'_> {
+    /// The path of the source field to cast this field from.
+    fn source(&self) -> TokenStream {
+        match &self.attr.from_field {
+            Some(members) => quote!(value #(.#members)*),
+            None => {
+                let member = &self.member;
+                quote!(value.#member)
+            }
+        }
+    }
+}
+
+/// A source type to cast from, which may be borrowed.
+struct Source {
+    lifetime: Option<Lifetime>,
+    ty: Type,
+    by_ref: bool,
+}
+
+impl Source {
+    fn new(ty: &Type) -> Self {
+        match ty {
+            Type::Reference(reference) => {
+                let mut reference = reference.clone();
+                let lifetime = reference
+                    .lifetime
+                    .get_or_insert_with(|| {
+                        Lifetime::new("'__source", proc_macro2::Span::call_site())
+                    })
+                    .clone();
+
+                Self {
+                    lifetime: Some(lifetime),
+                    ty: Type::Reference(reference),
+                    by_ref: true,
+                }
+            }
+            ty => Self {
+                lifetime: None,
+                ty: ty.clone(),
+                by_ref: false,
+            },
+        }
+    }
+
+    /// The type which a flattened field is cast from, and the expression to cast it from.
+    fn flattened(&self) -> (TokenStream, TokenStream) {
+        let ty = &self.ty;
+        if self.by_ref {
+            (quote!(#ty), quote!(value))
+        } else {
+            (quote!(&#ty), quote!(&value))
+        }
+    }
+
+    /// An owned copy of the given source field, if the source is borrowed.
+    fn take(&self, field: TokenStream) -> TokenStream {
+        if self.by_ref {
+            quote!(::core::clone::Clone::clone(&#field))
+        } else {
+            field
+        }
+    }
+}
+
+pub fn derive(
+    input: &DeriveInput,
+    data: &DataStruct,
+    container: &Container,
+) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let fields = data
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(i.into()),
+            };
+
+            attr::Field::parse(&field.attrs).map(|attr| Field {
+                member,
+                ty: &field.ty,
+                attr,
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let flattened: Vec<_> = (0..fields.len())
+        .map(|i| format_ident!("__flatten{}", i))
+        .collect();
+
+    let mut impls = TokenStream::new();
+
+    for source in container.from.iter().map(Source::new) {
+        let source_ty = &source.ty;
+        let (flat_ty, flat_value) = source.flattened();
+        let bounds = infer_bounds(input, &fields, &source, quote!(CastFrom));
+        let generics = impl_generics(input, container, source.lifetime.as_ref(), bounds);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+        let lets = fields
+            .iter()
+            .zip(&flattened)
+            .filter(|(field, _)| field.attr.flatten)
+            .map(|(field, var)| {
+                let ty = field.ty;
+                quote! {
+                    let #var = <#ty as ::safecast::CastFrom<#flat_ty>>::cast_from(#flat_value);
+                }
+            });
+
+        let values = fields.iter().zip(&flattened).map(|(field, var)| {
+            let member = &field.member;
+            if field.attr.flatten {
+                quote!(#member: #var)
+            } else {
+                let ty = field.ty;
+                let value = source.take(field.source());
+                quote!(#member: <#ty as ::safecast::CastFrom<_>>::cast_from(#value))
+            }
+        });
+
+        impls.extend(quote! {
+            impl #impl_generics ::safecast::CastFrom<#source_ty> for #name #ty_generics
+            #where_clause
+            {
+                #[allow(unused_variables)]
+                fn cast_from(value: #source_ty) -> Self {
+                    #(#lets)*
+                    Self { #(#values),* }
+                }
+            }
+        });
+    }
+
+    for source in container.try_from.iter().map(Source::new) {
+        let source_ty = &source.ty;
+        let (flat_ty, flat_value) = source.flattened();
+        let bounds = infer_bounds(input, &fields, &source, quote!(TryCastFrom));
+        let generics = impl_generics(input, container, source.lifetime.as_ref(), bounds);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+        let checks = fields.iter().map(|field| {
+            let ty = field.ty;
+            if field.attr.flatten {
+                quote!(<#ty as ::safecast::TryCastFrom<#flat_ty>>::can_cast_from(&#flat_value))
+            } else {
+                let value = field.source();
+                quote!(<#ty as ::safecast::TryCastFrom<_>>::can_cast_from(&#value))
+            }
+        });
+
+        let lets = fields
+            .iter()
+            .zip(&flattened)
+            .filter(|(field, _)| field.attr.flatten)
+            .map(|(field, var)| {
+                let ty = field.ty;
+                quote! {
+                    let #var =
+                        <#ty as ::safecast::TryCastFrom<#flat_ty>>::opt_cast_from(#flat_value)?;
+                }
+            });
+
+        let values = fields.iter().zip(&flattened).map(|(field, var)| {
+            let member = &field.member;
+            if field.attr.flatten {
+                quote!(#member: #var)
+            } else {
+                let ty = field.ty;
+                let value = source.take(field.source());
+                quote!(#member: <#ty as ::safecast::TryCastFrom<_>>::opt_cast_from(#value)?)
+            }
+        });
+
+        impls.extend(quote! {
+            impl #impl_generics ::safecast::TryCastFrom<#source_ty> for #name #ty_generics
+            #where_clause
+            {
+                #[allow(unused_variables)]
+                fn can_cast_from(value: &#source_ty) -> bool {
+                    true #(&& #checks)*
+                }
+
+                #[allow(unused_variables)]
+                fn opt_cast_from(value: #source_ty) -> Option<Self> {
+                    #(#lets)*
+                    Some(Self { #(#values),* })
+                }
+            }
+        });
+    }
+
+    if container.aggregate_errors && container.try_from.is_empty() {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`aggregate_errors` requires a `try_from` type",
+        ));
+    }
+
+    for source in container
+        .try_from
+        .iter()
+        .filter(|_| container.aggregate_errors)
+        .map(Source::new)
+    {
+        let source_ty = &source.ty;
+        let (flat_ty, flat_value) = source.flattened();
+        let mut bounds = infer_bounds(input, &fields, &source, quote!(TryCastFrom));
+        let params = Params::new(input, source_ty);
+
+        for field in &fields {
+            let ty = field.ty;
+            if field.attr.flatten && params.mentioned_by(ty) {
+                bounds.push(parse_quote!(#ty: ::safecast::TryCastFromFields<#flat_ty>));
+            } else if let Some(other) = params.counterpart(ty).filter(|_| !field.attr.flatten) {
+                bounds.push(parse_quote!(#other: ::core::fmt::Debug));
+            }
+        }
+
+        let generics = impl_generics(input, container, source.lifetime.as_ref(), bounds);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+        let checks = fields.iter().zip(&flattened).map(|(field, var)| {
+            let ty = field.ty;
+            let label = member_name(&field.member);
+
+            if field.attr.flatten {
+                quote! {
+                    let #var: Option<#ty> =
+                        match ::safecast::TryCastFromFields::try_cast_from_fields(#flat_value) {
+                            Ok(nested) => Some(nested),
+                            Err(nested) => {
+                                errors.extend_nested(#label, nested);
+                                None
+                            }
+                        };
+                }
+            } else {
+                let value = field.source();
+                // spanned so that a source field which is not `Debug` is reported at this field
+                let found = quote_spanned!(ty.span()=> ::safecast::__private::found(&#value));
+                quote! {
+                    if !<#ty as ::safecast::TryCastFrom<_>>::can_cast_from(&#value) {
+                        let found = #found;
+                        errors.push(::safecast::FieldError::new::<#ty>(#label, found));
+                    }
+                }
+            }
+        });
+
+        let vars: Vec<_> = fields
+            .iter()
+            .zip(&flattened)
+            .filter(|(field, _)| field.attr.flatten)
+            .map(|(_, var)| var)
+            .collect();
+
+        // the nested values are already cast, so only the plain fields are cast again
+        let values = fields.iter().zip(&flattened).map(|(field, var)| {
+            let member = &field.member;
+            if field.attr.flatten {
+                quote!(#member: #var)
+            } else {
+                let ty = field.ty;
+                let value = source.take(field.source());
+                quote!(#member: ::safecast::cast_after_check::<_, #ty>(#value))
+            }
+        });
+
+        impls.extend(quote! {
+            impl #impl_generics ::safecast::TryCastFromFields<#source_ty> for #name #ty_generics
+            #where_clause
+            {
+                fn try_cast_from_fields(
+                    value: #source_ty,
+                ) -> Result<Self, ::safecast::FieldErrors> {
+                    let mut errors = ::safecast::FieldErrors::new();
+                    #(#checks)*
+
+                    match (#(#vars,)*) {
+                        (#(Some(#vars),)*) if errors.is_empty() => Ok(Self { #(#values),* }),
+                        _ => Err(errors),
+                    }
+                }
+            }
+        });
+    }
+
+    for target in &container.into {
+        if let Some(field) = fields
+            .iter()
+            .find(|field| field.attr.flatten || field.attr.from_field.is_some())
+        {
+            return Err(syn::Error::new_spanned(
+                field.ty,
+                "a struct with `flatten` or `from_field` fields can't be cast `into` another type",
+            ));
+        }
+
+        let params = Params::new(input, target);
+        let mut bounds = Bounds::default();
+
+        for field in &fields {
+            if let Some(other) = params.counterpart(field.ty) {
+                bounds.cast(&other, field.ty, quote!(CastFrom));
+            }
+        }
+
+        let generics = impl_generics(input, container, None, bounds);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let target_path = type_path(target)?;
+        let values = fields.iter().map(|field| {
+            let member = &field.member;
+            quote!(#member: ::safecast::CastInto::cast_into(value.#member))
+        });
+
+        impls.extend(quote! {
+            impl #impl_generics ::safecast::CastFrom<#name #ty_generics> for #target
+            #where_clause
+            {
+                #[allow(unused_variables)]
+                fn cast_from(value: #name #ty_generics) -> Self {
+                    #target_path { #(#values),* }
+                }
+            }
+        });
+    }
+
+    Ok(impls)
+}
+
+/// The bounds inferred for casting each field whose type mentions a type parameter from `source`
+/// with `cast`, like `CastFrom`.
+fn infer_bounds(
+    input: &DeriveInput,
+    fields: &[Field],
+    source: &Source,
+    cast: TokenStream,
+) -> Bounds {
+    let params = Params::new(input, &source.ty);
+    let (flat_ty, _) = source.flattened();
+    let mut bounds = Bounds::default();
+
+    for field in fields {
+        let ty = field.ty;
+
+        if field.attr.flatten {
+            if params.mentioned_by(ty) {
+                bounds.push(parse_quote!(#ty: ::safecast::#cast<#flat_ty>));
+            }
+        } else if let Some(other) = params.counterpart(ty) {
+            bounds.cast(ty, &other, cast.clone());
+
+            if source.by_ref {
+                bounds.push(parse_quote!(#other: ::core::clone::Clone));
+            }
+        }
+    }
+
+    bounds
+}
+
+/// The name of a field to report in an error.
+fn member_name(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
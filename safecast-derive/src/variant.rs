@@ -0,0 +1,26 @@
+//! The name of the variant of each instance of an enum.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DataEnum, DeriveInput};
+
+pub fn derive(input: &DeriveInput, data: &DataEnum) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let arms = data.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let label = ident.to_string();
+        quote!(Self::#ident { .. } => #label,)
+    });
+
+    quote! {
+        impl #impl_generics ::safecast::VariantName for #name #ty_generics #where_clause {
+            fn variant_name(&self) -> &'static str {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    }
+}
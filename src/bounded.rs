@@ -0,0 +1,236 @@
+//! Cast checks with a bounded cost, for untrusted recursive input.
+
+use super::{CastFrom, TryCastFrom};
+
+/// Limits on the cost of a [`TryCastFromBounded::can_cast_from_bounded`] check.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CastLimits {
+    max_depth: usize,
+    max_items: usize,
+}
+
+impl CastLimits {
+    /// Construct a new set of limits on the nesting depth and total number of items to check.
+    pub fn new(max_depth: usize, max_items: usize) -> Self {
+        Self {
+            max_depth,
+            max_items,
+        }
+    }
+
+    /// The maximum nesting depth to check.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// The maximum total number of items to check.
+    pub fn max_items(&self) -> usize {
+        self.max_items
+    }
+}
+
+/// The remaining budget of a bounded cast check, threaded through nested calls to
+/// [`TryCastFromBounded::can_cast_from_bounded`].
+#[derive(Clone, Debug)]
+pub struct CastBudget {
+    limits: CastLimits,
+    depth: usize,
+    items: usize,
+    exhausted: bool,
+}
+
+impl CastBudget {
+    /// Construct a new, full budget with the given `limits`.
+    pub fn new(limits: CastLimits) -> Self {
+        Self {
+            limits,
+            depth: 0,
+            items: 0,
+            exhausted: false,
+        }
+    }
+
+    /// The current nesting depth.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The number of items checked so far.
+    pub fn items(&self) -> usize {
+        self.items
+    }
+
+    /// Returns `true` if a check has exceeded the limits of this budget.
+    /// A check which exceeds its budget returns `false`, so use this method to distinguish an
+    /// input which is too expensive to check from an input which does not match.
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// Count one item against this budget. Returns `false` if the budget is exhausted.
+    pub fn visit(&mut self) -> bool {
+        if self.exhausted || self.items >= self.limits.max_items {
+            self.exhausted = true;
+            false
+        } else {
+            self.items += 1;
+            true
+        }
+    }
+
+    /// Call `check` one level of nesting deeper.
+    /// Returns `false` without calling `check` if this would exceed the maximum depth.
+    pub fn descend<F: FnOnce(&mut Self) -> bool>(&mut self, check: F) -> bool {
+        if self.exhausted || self.depth >= self.limits.max_depth {
+            self.exhausted = true;
+            return false;
+        }
+
+        self.depth += 1;
+        let can_cast = check(self);
+        self.depth -= 1;
+        can_cast
+    }
+
+    /// Check that every one of `items`, one level of nesting deeper, can be cast into `U`,
+    /// counting each item against this budget and stopping at the first which cannot.
+    pub fn all<'a, T: 'a, U, I>(&mut self, items: I) -> bool
+    where
+        U: TryCastFromBounded<T>,
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.descend(|budget| {
+            items
+                .into_iter()
+                .all(|item| budget.visit() && U::can_cast_from_bounded(item, budget))
+        })
+    }
+}
+
+/// Trait for testing whether a value can be cast into `Self` with a bounded cost.
+///
+/// An implementation for a recursive type should check nested values using
+/// [`CastBudget::all`] or [`CastBudget::descend`], so that a deeply nested or very large input
+/// exhausts the budget instead of forcing a full traversal. Besides the blanket implementation
+/// for infallible casts, this crate implements it for the collection wrappers [`Each`](crate::Each)
+/// and [`Chunks`](crate::Chunks) and for casts between [`Pairs`](crate::Pairs) and maps, each of
+/// which checks its elements with the element type's own bounded check. To check the items of an
+/// iterator, use [`MatchEach::matches_all_bounded`](crate::MatchEach::matches_all_bounded).
+/// Example:
+/// ```
+/// use safecast::{CastBudget, CastLimits, Match, TryCastFrom, TryCastFromBounded};
+///
+/// enum Value {
+///     Int(u64),
+///     List(Vec<Value>),
+/// }
+///
+/// struct Ints(Vec<u64>);
+///
+/// impl TryCastFrom<Value> for Ints {
+///     fn can_cast_from(value: &Value) -> bool {
+///         match value {
+///             Value::List(items) => items.iter().all(|item| matches!(item, Value::Int(_))),
+///             Value::Int(_) => false,
+///         }
+///     }
+///
+///     fn opt_cast_from(value: Value) -> Option<Self> {
+///         match value {
+///             Value::List(items) => items
+///                 .into_iter()
+///                 .map(|item| match item {
+///                     Value::Int(i) => Some(i),
+///                     Value::List(_) => None,
+///                 })
+///                 .collect::<Option<Vec<u64>>>()
+///                 .map(Self),
+///             Value::Int(_) => None,
+///         }
+///     }
+/// }
+///
+/// impl TryCastFromBounded<Value> for Ints {
+///     fn can_cast_from_bounded(value: &Value, budget: &mut CastBudget) -> bool {
+///         match value {
+///             Value::List(items) => budget.descend(|budget| {
+///                 items
+///                     .iter()
+///                     .all(|item| budget.visit() && matches!(item, Value::Int(_)))
+///             }),
+///             Value::Int(_) => false,
+///         }
+///     }
+/// }
+///
+/// let value = Value::List(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+/// assert!(value.matches_bounded::<Ints>(CastLimits::new(1, 4)));
+/// assert!(!value.matches_bounded::<Ints>(CastLimits::new(1, 3)));
+/// ```
+pub trait TryCastFromBounded<T>: TryCastFrom<T> {
+    /// Test if `value` can be cast into `Self` within the given `budget`.
+    /// Returns `false` if the budget is exhausted.
+    fn can_cast_from_bounded(value: &T, budget: &mut CastBudget) -> bool;
+}
+
+impl<F, T: CastFrom<F>> TryCastFromBounded<F> for T {
+    fn can_cast_from_bounded(_: &F, budget: &mut CastBudget) -> bool {
+        !budget.is_exhausted()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Match;
+
+    #[allow(dead_code)]
+    enum Value {
+        Int(u64),
+        List(Vec<Value>),
+    }
+
+    struct Depth;
+
+    impl TryCastFrom<Value> for Depth {
+        fn can_cast_from(_: &Value) -> bool {
+            true
+        }
+
+        fn opt_cast_from(_: Value) -> Option<Self> {
+            Some(Self)
+        }
+    }
+
+    impl TryCastFromBounded<Value> for Depth {
+        fn can_cast_from_bounded(value: &Value, budget: &mut CastBudget) -> bool {
+            match value {
+                Value::Int(_) => true,
+                Value::List(items) => budget.all::<_, Self, _>(items),
+            }
+        }
+    }
+
+    fn nested(depth: usize) -> Value {
+        (0..depth).fold(Value::Int(0), |value, _| Value::List(vec![value]))
+    }
+
+    #[test]
+    fn test_bounded_depth() {
+        let value = nested(10);
+        assert!(value.matches_bounded::<Depth>(CastLimits::new(10, 100)));
+        assert!(!value.matches_bounded::<Depth>(CastLimits::new(9, 100)));
+
+        let mut budget = CastBudget::new(CastLimits::new(9, 100));
+        assert!(!Depth::can_cast_from_bounded(&value, &mut budget));
+        assert!(budget.is_exhausted());
+        assert_eq!(budget.depth(), 0);
+    }
+
+    #[test]
+    fn test_bounded_items() {
+        let value = Value::List((0..10).map(Value::Int).collect());
+        assert!(value.matches_bounded::<Depth>(CastLimits::new(1, 11)));
+        assert!(!value.matches_bounded::<Depth>(CastLimits::new(1, 10)));
+    }
+}
@@ -0,0 +1,146 @@
+//! Casting into an existing buffer, to reuse its allocation in hot loops.
+
+use std::borrow::Cow;
+
+/// Trait for defining a cast operation into an existing instance of `Self`, replacing its
+/// contents and reusing an existing allocation instead of allocating a new one: a borrowed
+/// source is copied into the buffer's allocation, and an owned source donates its own allocation
+/// to the buffer. When `can_cast_from_in` returns `true`, calling `opt_cast_from_in` *must*
+/// return `Some(...)`. If the cast fails, the buffer is left unchanged.
+///
+/// Note that owned casts which move their allocation from source to target, like
+/// `String::cast_from(Cow<str>)` or casting a `String` into a `String`-holding enum variant with
+/// [`as_type!`](crate::as_type), never reallocate, so they don't need a separate buffer.
+///
+/// Example:
+/// ```
+/// use safecast::TryCastFromIn;
+///
+/// let lines: [&[u8]; 3] = [b"one", b"two", b"\xff"];
+///
+/// let mut buffer = String::with_capacity(64);
+/// let mut valid = 0;
+/// for line in lines {
+///     if let Some(line) = String::opt_cast_from_in(line, &mut buffer) {
+///         assert_eq!(line.len(), 3);
+///         valid += 1;
+///     }
+/// }
+///
+/// assert_eq!(valid, 2);
+/// assert_eq!(buffer, "two");
+/// assert_eq!(buffer.capacity(), 64);
+/// ```
+pub trait TryCastFromIn<T>: Sized {
+    /// Test if `value` can be cast into `Self`.
+    fn can_cast_from_in(value: &T) -> bool;
+
+    /// Replace the contents of `buffer` with `value`, if possible, and return the buffer.
+    fn opt_cast_from_in(value: T, buffer: &mut Self) -> Option<&mut Self>;
+}
+
+impl<'a> TryCastFromIn<&'a str> for String {
+    fn can_cast_from_in(_: &&'a str) -> bool {
+        true
+    }
+
+    fn opt_cast_from_in<'b>(value: &'a str, buffer: &'b mut Self) -> Option<&'b mut Self> {
+        buffer.clear();
+        buffer.push_str(value);
+        Some(buffer)
+    }
+}
+
+impl TryCastFromIn<String> for String {
+    fn can_cast_from_in(_: &String) -> bool {
+        true
+    }
+
+    fn opt_cast_from_in(value: String, buffer: &mut Self) -> Option<&mut Self> {
+        *buffer = value;
+        Some(buffer)
+    }
+}
+
+impl<'a> TryCastFromIn<Cow<'a, str>> for String {
+    fn can_cast_from_in(_: &Cow<'a, str>) -> bool {
+        true
+    }
+
+    fn opt_cast_from_in<'b>(value: Cow<'a, str>, buffer: &'b mut Self) -> Option<&'b mut Self> {
+        match value {
+            Cow::Borrowed(value) => Self::opt_cast_from_in(value, buffer),
+            Cow::Owned(value) => Self::opt_cast_from_in(value, buffer),
+        }
+    }
+}
+
+impl<'a> TryCastFromIn<&'a [u8]> for String {
+    fn can_cast_from_in(value: &&'a [u8]) -> bool {
+        std::str::from_utf8(value).is_ok()
+    }
+
+    fn opt_cast_from_in<'b>(value: &'a [u8], buffer: &'b mut Self) -> Option<&'b mut Self> {
+        let value = std::str::from_utf8(value).ok()?;
+        Self::opt_cast_from_in(value, buffer)
+    }
+}
+
+impl TryCastFromIn<Vec<u8>> for String {
+    fn can_cast_from_in(value: &Vec<u8>) -> bool {
+        std::str::from_utf8(value).is_ok()
+    }
+
+    fn opt_cast_from_in(value: Vec<u8>, buffer: &mut Self) -> Option<&mut Self> {
+        let value = String::from_utf8(value).ok()?;
+        Self::opt_cast_from_in(value, buffer)
+    }
+}
+
+impl TryCastFromIn<char> for String {
+    fn can_cast_from_in(_: &char) -> bool {
+        true
+    }
+
+    fn opt_cast_from_in(value: char, buffer: &mut Self) -> Option<&mut Self> {
+        buffer.clear();
+        buffer.push(value);
+        Some(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cast_from_in_borrowed() {
+        let mut buffer = String::with_capacity(16);
+        let ptr = buffer.as_ptr();
+
+        String::opt_cast_from_in("hello", &mut buffer).unwrap();
+        String::opt_cast_from_in('x', &mut buffer).unwrap();
+        assert_eq!(buffer, "x");
+
+        assert!(!String::can_cast_from_in(&&b"\xff"[..]));
+        assert!(String::opt_cast_from_in(&b"\xff"[..], &mut buffer).is_none());
+        assert_eq!(buffer, "x");
+        assert_eq!(buffer.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_cast_from_in_owned() {
+        let bytes = b"owned".to_vec();
+        let ptr = bytes.as_ptr();
+
+        let mut buffer = String::new();
+        String::opt_cast_from_in(bytes, &mut buffer).unwrap();
+        assert_eq!(buffer, "owned");
+        assert_eq!(buffer.as_ptr(), ptr);
+
+        let value: Cow<str> = Cow::Owned("cow".to_string());
+        let ptr = value.as_ptr();
+        String::opt_cast_from_in(value, &mut buffer).unwrap();
+        assert_eq!(buffer.as_ptr(), ptr);
+    }
+}
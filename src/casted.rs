@@ -0,0 +1,141 @@
+//! A [`Casted`] wrapper which records the provenance of a cast value.
+
+use std::any::type_name;
+use std::fmt;
+use std::ops::Deref;
+
+use super::{CastFrom, TryCastFrom};
+
+/// A value of type `T` which records the name of each type it was cast from, in order.
+///
+/// Generic `CastFrom<F>` and `TryCastFrom<F>` implementations for `Casted<T>` would overlap with
+/// the blanket implementations for types which implement [`From`] (starting with the reflexive
+/// `From<Casted<T>>`), so a `Casted` value is constructed with [`Casted::cast_from_source`] or
+/// [`Casted::opt_cast_from_source`], chained with [`Casted::cast`] or [`Casted::opt_cast`], or
+/// returned by [`CastPipeline::try_run_casted`](crate::CastPipeline::try_run_casted).
+/// Example:
+/// ```
+/// use safecast::{Casted, Parsed};
+///
+/// let port = Casted::<String>::cast_from_source("8080");
+/// let port = port.opt_cast::<Parsed<u16>>().unwrap();
+///
+/// assert_eq!(port.0, 8080);
+/// assert_eq!(port.hops(), ["&str", "alloc::string::String"]);
+/// assert_eq!(port.source_type(), Some("&str"));
+/// ```
+#[derive(Clone)]
+pub struct Casted<T> {
+    value: T,
+    hops: Vec<&'static str>,
+}
+
+impl<T> Casted<T> {
+    pub(crate) fn new(value: T, hops: Vec<&'static str>) -> Self {
+        Self { value, hops }
+    }
+
+    /// Cast `value` into `T`, recording its source type.
+    pub fn cast_from_source<F>(value: F) -> Self
+    where
+        T: CastFrom<F>,
+    {
+        Self::new(T::cast_from(value), vec![type_name::<F>()])
+    }
+
+    /// Cast `value` into `T`, if possible, recording its source type.
+    pub fn opt_cast_from_source<F>(value: F) -> Option<Self>
+    where
+        T: TryCastFrom<F>,
+    {
+        T::opt_cast_from(value).map(|value| Self::new(value, vec![type_name::<F>()]))
+    }
+
+    /// Cast this value into `U`, appending `T` to its provenance.
+    pub fn cast<U: CastFrom<T>>(self) -> Casted<U> {
+        let mut hops = self.hops;
+        hops.push(type_name::<T>());
+        Casted::new(U::cast_from(self.value), hops)
+    }
+
+    /// Cast this value into `U`, if possible, appending `T` to its provenance.
+    pub fn opt_cast<U: TryCastFrom<T>>(self) -> Option<Casted<U>> {
+        let mut hops = self.hops;
+        hops.push(type_name::<T>());
+        U::opt_cast_from(self.value).map(|value| Casted::new(value, hops))
+    }
+
+    /// The names of the types which this value was cast from, from the original source type to
+    /// the type which was cast into `T`.
+    pub fn hops(&self) -> &[&'static str] {
+        &self.hops
+    }
+
+    /// The name of the original source type of this value, if any.
+    pub fn source_type(&self) -> Option<&'static str> {
+        self.hops.first().copied()
+    }
+
+    /// Return the cast value, discarding its provenance.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> AsRef<T> for Casted<T> {
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> Deref for Casted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: PartialEq> PartialEq for Casted<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for Casted<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for Casted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Casted")
+            .field("value", &self.value)
+            .field("hops", &self.hops)
+            .finish()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Casted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.value, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parsed;
+
+    #[test]
+    fn test_casted() {
+        let value = Casted::<u64>::cast_from_source(1u8);
+        assert_eq!(value.hops(), ["u8"]);
+
+        let value = Casted::<String>::cast_from_source("256").opt_cast::<Parsed<u8>>();
+        assert!(value.is_none());
+
+        let value = Casted::<String>::cast_from_source("a").cast::<Box<str>>();
+        assert_eq!(value.hops(), ["&str", "alloc::string::String"]);
+        assert_eq!(&**value, "a");
+
+        assert!(Casted::<Parsed<u8>>::opt_cast_from_source("x").is_none());
+    }
+}
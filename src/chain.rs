@@ -0,0 +1,161 @@
+//! Helpers to find an error of a concrete type in the [source](std::error::Error::source) chain
+//! of another error.
+
+use std::error::Error;
+use std::iter::FusedIterator;
+
+use super::{expect_cast, TryCastFrom};
+
+/// An iterator over an error and each of its sources in turn, returned by [`error_chain`].
+#[derive(Clone, Debug)]
+pub struct ErrorChain<'a> {
+    next: Option<&'a (dyn Error + 'static)>,
+}
+
+impl<'a> Iterator for ErrorChain<'a> {
+    type Item = &'a (dyn Error + 'static);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let error = self.next?;
+        self.next = error.source();
+        Some(error)
+    }
+}
+
+impl FusedIterator for ErrorChain<'_> {}
+
+/// Iterate over `error` and each of its sources in turn.
+pub fn error_chain<'a>(error: &'a (dyn Error + 'static)) -> ErrorChain<'a> {
+    ErrorChain { next: Some(error) }
+}
+
+/// Borrow the first error of type `E` in the source chain of `error` (including `error` itself).
+///
+/// Example:
+/// ```
+/// use safecast::{find_cast, CastError, CastPipeline, Parsed};
+///
+/// let pipeline = CastPipeline::<&'static str>::new().step::<Parsed<u8>>();
+/// let err = pipeline.try_run("256").unwrap_err();
+///
+/// let cause = find_cast::<CastError>(&err).unwrap();
+/// assert!(cause.target_type().ends_with("Parsed<u8>"));
+/// assert!(find_cast::<std::fmt::Error>(&err).is_none());
+/// ```
+pub fn find_cast<'a, E: Error + 'static>(error: &'a (dyn Error + 'static)) -> Option<&'a E> {
+    error_chain(error).find_map(|error| error.downcast_ref())
+}
+
+/// Cast the first error of type `E` in the source chain of `error` (including `error` itself)
+/// which can be cast into `T`, if any.
+///
+/// Example:
+/// ```
+/// use std::fmt;
+/// use safecast::{find_cast_from, TryCastFrom};
+///
+/// #[derive(Debug)]
+/// struct DbError {
+///     code: u16,
+/// }
+///
+/// impl fmt::Display for DbError {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "database error {}", self.code)
+///     }
+/// }
+///
+/// impl std::error::Error for DbError {}
+///
+/// struct Retry;
+///
+/// impl TryCastFrom<&DbError> for Retry {
+///     fn can_cast_from(error: &&DbError) -> bool {
+///         error.code == 503
+///     }
+///
+///     fn opt_cast_from(error: &DbError) -> Option<Self> {
+///         if error.code == 503 {
+///             Some(Self)
+///         } else {
+///             None
+///         }
+///     }
+/// }
+///
+/// let err = DbError { code: 503 };
+/// assert!(find_cast_from::<DbError, Retry>(&err).is_some());
+///
+/// let err = DbError { code: 404 };
+/// assert!(find_cast_from::<DbError, Retry>(&err).is_none());
+/// ```
+pub fn find_cast_from<'a, E, T>(error: &'a (dyn Error + 'static)) -> Option<T>
+where
+    E: Error + 'static,
+    T: TryCastFrom<&'a E>,
+{
+    error_chain(error)
+        .filter_map(|error| error.downcast_ref::<E>())
+        .find(|error| T::can_cast_from(error))
+        .map(|error| expect_cast::<&E, T>(T::opt_cast_from(error)))
+}
+
+/// Iterate over every error of type `E` in the source chain of `error` (including `error`
+/// itself), from the outermost to the innermost.
+pub fn find_all_cast<'a, E: Error + 'static>(
+    error: &'a (dyn Error + 'static),
+) -> impl Iterator<Item = &'a E> {
+    error_chain(error).filter_map(|error| error.downcast_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::{CastRegistry, RegistryError};
+    use crate::{CastError, Parsed};
+
+    #[test]
+    fn test_find_cast() {
+        let registry = CastRegistry::new();
+        registry.register::<&'static str, Parsed<u8>>("a").unwrap();
+
+        let err = registry.cast::<_, Parsed<u8>>("a", "x").unwrap_err();
+        assert_eq!(error_chain(&err).count(), 2);
+        assert!(find_cast::<RegistryError>(&err).is_some());
+        assert_eq!(
+            find_cast::<CastError>(&err),
+            Some(&CastError::new::<&str, Parsed<u8>>())
+        );
+
+        let err = registry.cast::<u8, u64>("a", 1).unwrap_err();
+        assert_eq!(error_chain(&err).count(), 1);
+        assert!(find_cast::<CastError>(&err).is_none());
+        assert_eq!(find_all_cast::<RegistryError>(&err).count(), 1);
+    }
+
+    #[test]
+    fn test_find_cast_from() {
+        struct Target(&'static str);
+
+        impl TryCastFrom<&CastError> for Target {
+            fn can_cast_from(error: &&CastError) -> bool {
+                error.source_type() == "u8"
+            }
+
+            fn opt_cast_from(error: &CastError) -> Option<Self> {
+                if error.source_type() == "u8" {
+                    Some(Self(error.target_type()))
+                } else {
+                    None
+                }
+            }
+        }
+
+        let err = RegistryError::Cast(CastError::new::<u8, bool>());
+        let target = find_cast_from::<CastError, Target>(&err).unwrap();
+        assert_eq!(target.0, "bool");
+
+        let err = RegistryError::Cast(CastError::new::<u16, bool>());
+        assert!(find_cast_from::<CastError, Target>(&err).is_none());
+    }
+}
@@ -0,0 +1,252 @@
+//! A [`Chunks`] wrapper to regroup a sequence into fixed-size arrays, casting each element.
+
+#[cfg(feature = "allocator_api")]
+use std::alloc::{Allocator, Global};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+use super::{expect_cast, CastBudget, TryCastFrom, TryCastFromBounded};
+
+/// A sequence of arrays of length `N`, each of whose elements was cast from the corresponding
+/// element of a flat source sequence.
+///
+/// The cast fails if the length of the source is not a multiple of `N` (or `N` is zero), or if
+/// any element can't be cast. Example:
+/// ```
+/// use safecast::{Chunks, TryCastFrom};
+///
+/// let samples = [1u16, 2, 3, 4, 5, 6];
+/// let frames = Chunks::<u32, 2>::opt_cast_from(&samples[..]).unwrap();
+/// assert_eq!(frames.into_inner(), [[1u32, 2], [3, 4], [5, 6]]);
+///
+/// assert!(!Chunks::<u32, 4>::can_cast_from(&&samples[..]));
+///
+/// let fields = vec!["1", "2", "x", "4"];
+/// assert!(Chunks::<safecast::Parsed<u8>, 2>::opt_cast_from(fields).is_none());
+/// ```
+///
+/// With the `allocator_api` feature, the chunks cast from a `Vec<T, A>` are allocated with a
+/// clone of its allocator `A`.
+///
+/// A `Chunks` also implements [`TryCastFromBounded`], counting each element of the source
+/// against the budget one level of nesting deeper than the sequence itself.
+#[derive(Clone, Debug)]
+pub struct Chunks<U, const N: usize, #[cfg(feature = "allocator_api")] A: Allocator = Global>(
+    #[cfg(not(feature = "allocator_api"))] pub Vec<[U; N]>,
+    #[cfg(feature = "allocator_api")] pub Vec<[U; N], A>,
+);
+
+impl<U, const N: usize> Default for Chunks<U, N> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+fn is_chunked<T, const N: usize>(value: &[T]) -> bool {
+    N > 0 && value.chunks_exact(N).remainder().is_empty()
+}
+
+fn can_cast_chunks<T, U: TryCastFrom<T>, const N: usize>(value: &[T]) -> bool {
+    is_chunked::<T, N>(value) && value.iter().all(U::can_cast_from)
+}
+
+fn can_cast_chunks_bounded<T, U, const N: usize>(value: &[T], budget: &mut CastBudget) -> bool
+where
+    U: TryCastFromBounded<T>,
+{
+    is_chunked::<T, N>(value) && budget.all::<T, U, _>(value)
+}
+
+/// Cast `len / N` chunks of `items`, calling `push` with each one.
+fn cast_chunks<T, U, I, P, const N: usize>(len: usize, items: I, mut push: P) -> Option<()>
+where
+    U: TryCastFrom<T>,
+    I: Iterator<Item = T>,
+    P: FnMut([U; N]),
+{
+    let mut items = items.map(U::opt_cast_from);
+
+    for _ in 0..(len / N) {
+        let mut failed = false;
+
+        // stop casting the elements of this chunk as soon as one fails
+        let chunk: [Option<U>; N] = std::array::from_fn(|_| {
+            let item = if failed { None } else { items.next().flatten() };
+            failed = item.is_none();
+            item
+        });
+
+        if failed {
+            return None;
+        }
+
+        push(chunk.map(expect_cast::<T, U>));
+    }
+
+    Some(())
+}
+
+macro_rules! chunks {
+    ([$($g:tt)*] $vec:ty => $chunks:ty, $inner:ty, |$value:ident| $new:expr) => {
+        impl<U, const N: usize, $($g)*> $chunks {
+            /// Return the cast chunks.
+            pub fn into_inner(self) -> $inner {
+                self.0
+            }
+        }
+
+        // implemented by hand so that the allocator need not be comparable
+        impl<U: PartialEq, const N: usize, $($g)*> PartialEq for $chunks {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl<U: Eq, const N: usize, $($g)*> Eq for $chunks {}
+
+        impl<U: Hash, const N: usize, $($g)*> Hash for $chunks {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.hash(state)
+            }
+        }
+
+        impl<U, const N: usize, $($g)*> Deref for $chunks {
+            type Target = $inner;
+
+            fn deref(&self) -> &$inner {
+                &self.0
+            }
+        }
+
+        impl<T, U: TryCastFrom<T>, const N: usize, $($g)*> TryCastFrom<$vec> for $chunks {
+            fn can_cast_from(value: &$vec) -> bool {
+                can_cast_chunks::<T, U, N>(value)
+            }
+
+            fn opt_cast_from($value: $vec) -> Option<Self> {
+                if is_chunked::<T, N>(&$value) {
+                    let mut chunks = $new;
+                    cast_chunks($value.len(), $value.into_iter(), |chunk| chunks.push(chunk))?;
+                    Some(Self(chunks))
+                } else {
+                    None
+                }
+            }
+        }
+
+        impl<T, U: TryCastFromBounded<T>, const N: usize, $($g)*> TryCastFromBounded<$vec>
+            for $chunks
+        {
+            fn can_cast_from_bounded(value: &$vec, budget: &mut CastBudget) -> bool {
+                can_cast_chunks_bounded::<T, U, N>(value, budget)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "allocator_api"))]
+chunks!([] Vec<T> => Chunks<U, N>, Vec<[U; N]>, |value| Vec::with_capacity(value.len() / N));
+#[cfg(feature = "allocator_api")]
+chunks!([A: Allocator + Clone] Vec<T, A> => Chunks<U, N, A>, Vec<[U; N], A>, |value| {
+    Vec::with_capacity_in(value.len() / N, value.allocator().clone())
+});
+
+impl<'a, T: Clone, U: TryCastFrom<T>, const N: usize> TryCastFrom<&'a [T]> for Chunks<U, N> {
+    fn can_cast_from(value: &&'a [T]) -> bool {
+        can_cast_chunks::<T, U, N>(value)
+    }
+
+    fn opt_cast_from(value: &'a [T]) -> Option<Self> {
+        if is_chunked::<T, N>(value) {
+            let mut chunks = Vec::with_capacity(value.len() / N);
+            cast_chunks(value.len(), value.iter().cloned(), |chunk| {
+                chunks.push(chunk)
+            })?;
+            Some(Self(chunks))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T: Clone, U, const N: usize> TryCastFromBounded<&'a [T]> for Chunks<U, N>
+where
+    U: TryCastFromBounded<T>,
+{
+    fn can_cast_from_bounded(value: &&'a [T], budget: &mut CastBudget) -> bool {
+        can_cast_chunks_bounded::<T, U, N>(value, budget)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CastLimits, Match, Parsed};
+
+    #[test]
+    fn test_chunks() {
+        let fields = vec!["1", "2", "3", "4", "5", "6"];
+        assert!(fields.matches::<Chunks<Parsed<u8>, 3>>());
+        assert!(!fields.matches::<Chunks<Parsed<u8>, 4>>());
+        assert!(!fields.matches::<Chunks<Parsed<u8>, 0>>());
+
+        let rows = Chunks::<Parsed<u8>, 3>::opt_cast_from(fields).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1], [Parsed(4), Parsed(5), Parsed(6)]);
+
+        let empty: &[u8] = &[];
+        assert_eq!(Chunks::<u64, 2>::opt_cast_from(empty), Some(Chunks(vec![])));
+        assert!(Chunks::<u64, 0>::opt_cast_from(empty).is_none());
+
+        let invalid = vec!["1", "256"];
+        assert!(!invalid.matches::<Chunks<Parsed<u8>, 2>>());
+        assert!(Chunks::<Parsed<u8>, 2>::opt_cast_from(invalid).is_none());
+    }
+
+    #[test]
+    fn test_chunks_short_circuit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CASTS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Small;
+
+        impl TryCastFrom<u8> for Small {
+            fn can_cast_from(value: &u8) -> bool {
+                *value < 10
+            }
+
+            fn opt_cast_from(value: u8) -> Option<Self> {
+                CASTS.fetch_add(1, Ordering::Relaxed);
+                Self::can_cast_from(&value).then_some(Self)
+            }
+        }
+
+        assert!(Chunks::<Small, 4>::opt_cast_from(vec![1u8, 20, 3, 4, 5, 6, 7, 8]).is_none());
+        assert_eq!(CASTS.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_chunks_bounded() {
+        let samples = vec![1u8, 2, 3, 4];
+        assert!(samples.matches_bounded::<Chunks<u32, 2>>(CastLimits::new(1, 5)));
+        assert!(!samples.matches_bounded::<Chunks<u32, 2>>(CastLimits::new(1, 4)));
+        assert!(!samples.matches_bounded::<Chunks<u32, 3>>(CastLimits::new(1, 5)));
+    }
+
+    #[cfg(feature = "allocator_api")]
+    #[test]
+    fn test_chunks_allocator() {
+        use std::alloc::System;
+
+        let mut fields = Vec::new_in(System);
+        fields.extend(["1", "2", "3", "4"]);
+
+        let rows: Vec<[Parsed<u8>; 2], System> =
+            Chunks::<Parsed<u8>, 2, System>::opt_cast_from(fields)
+                .unwrap()
+                .into_inner();
+
+        assert_eq!(rows, [[Parsed(1), Parsed(2)], [Parsed(3), Parsed(4)]]);
+    }
+}
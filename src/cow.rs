@@ -0,0 +1,112 @@
+//! Casting a borrowed container into a [`Cow`], which borrows when possible.
+
+use std::borrow::{Borrow, Cow};
+
+use super::{AsType, TryCastFromRef};
+
+/// Blanket implementation of convenience methods to cast a borrowed container (such as a value
+/// `enum`) into a [`Cow<B>`](Cow): `Borrowed` if the container already holds an instance of
+/// `B::Owned` according to [`AsType`], otherwise `Owned`, converted using the
+/// [`TryCastFromRef`] implementation of `B::Owned`. Do not implement this trait.
+///
+/// Example:
+/// ```
+/// use std::borrow::Cow;
+/// use safecast::{as_type, TryCastFrom, TryCastIntoCow};
+///
+/// enum Value {
+///     Int(u64),
+///     String(String),
+/// }
+///
+/// as_type!(Value, Int, u64);
+/// as_type!(Value, String, String);
+///
+/// impl<'a> TryCastFrom<&'a Value> for String {
+///     fn can_cast_from(_: &&'a Value) -> bool {
+///         true
+///     }
+///
+///     fn opt_cast_from(value: &'a Value) -> Option<Self> {
+///         match value {
+///             Value::Int(i) => Some(i.to_string()),
+///             Value::String(s) => Some(s.clone()),
+///         }
+///     }
+/// }
+///
+/// let string = Value::String("hello".to_string());
+/// assert!(matches!(string.opt_cast_into_cow::<str>(), Some(Cow::Borrowed("hello"))));
+///
+/// let int = Value::Int(1);
+/// assert!(matches!(int.opt_cast_into_cow::<str>(), Some(Cow::Owned(s)) if s == "1"));
+/// ```
+pub trait TryCastIntoCow {
+    /// Test if `self` can be borrowed or cast as an instance of `B`.
+    fn can_cast_into_cow<B>(&self) -> bool
+    where
+        B: ToOwned + ?Sized,
+        Self: AsType<B::Owned>,
+        B::Owned: TryCastFromRef<Self>,
+    {
+        self.as_type().is_some() || B::Owned::can_cast_from_ref(self)
+    }
+
+    /// Borrow `self` as an instance of `B` if possible, otherwise cast it into `B::Owned`.
+    fn opt_cast_into_cow<B>(&self) -> Option<Cow<'_, B>>
+    where
+        B: ToOwned + ?Sized,
+        Self: AsType<B::Owned>,
+        B::Owned: TryCastFromRef<Self>,
+    {
+        match self.as_type() {
+            Some(owned) => Some(Cow::Borrowed(owned.borrow())),
+            None => B::Owned::opt_cast_from_ref(self).map(Cow::Owned),
+        }
+    }
+}
+
+impl<C> TryCastIntoCow for C {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{as_type, TryCastFrom};
+
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    enum Value {
+        Bytes(Vec<u8>),
+        Int(u8),
+        None,
+    }
+
+    as_type!(Value, Bytes, Vec<u8>);
+
+    impl<'a> TryCastFrom<&'a Value> for Vec<u8> {
+        fn can_cast_from(value: &&'a Value) -> bool {
+            !matches!(value, Value::None)
+        }
+
+        fn opt_cast_from(value: &'a Value) -> Option<Self> {
+            match value {
+                Value::Bytes(bytes) => Some(bytes.clone()),
+                Value::Int(i) => Some(vec![*i]),
+                Value::None => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_cast_into_cow() {
+        let bytes = Value::Bytes(vec![1, 2]);
+        let cow = bytes.opt_cast_into_cow::<[u8]>().unwrap();
+        assert!(matches!(cow, Cow::Borrowed([1, 2])));
+
+        let int = Value::Int(3);
+        let cow = int.opt_cast_into_cow::<[u8]>().unwrap();
+        assert!(matches!(cow, Cow::Owned(ref v) if v == &[3]));
+
+        assert!(!Value::None.can_cast_into_cow::<[u8]>());
+        assert!(Value::None.opt_cast_into_cow::<[u8]>().is_none());
+    }
+}
@@ -0,0 +1,255 @@
+//! Checked casts between [`Decimal`] and the primitive numeric types and strings, using
+//! [`rust_decimal`].
+
+use std::ops::Deref;
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+use super::TryCastFrom;
+
+/// A number cast from another numeric type (or a string) without loss of precision.
+///
+/// Casting between [`Decimal`] and a primitive type directly would overlap with the blanket
+/// implementation of [`TryCastFrom`] for types which implement [`From`], so this explicit
+/// wrapper is used instead. A float is cast by its exact binary value, so a float like `0.1`,
+/// whose exact value has more decimal places than a `Decimal` can hold, can't be cast; to cast a
+/// float into the shortest decimal which rounds to it, use [`Decimal::from_f64`](
+/// rust_decimal::prelude::FromPrimitive::from_f64) instead. Example:
+/// ```
+/// use rust_decimal::Decimal;
+/// use safecast::{Exact, Match, TryCastFrom};
+///
+/// let price = Decimal::new(1999, 2);
+/// assert!(!price.matches::<Exact<u64>>());
+/// assert_eq!(Exact::<u64>::opt_cast_from(Decimal::new(2000, 2)), Some(Exact(20)));
+///
+/// assert_eq!(*Exact::<Decimal>::opt_cast_from("19.99").unwrap(), price);
+/// assert_eq!(*Exact::<Decimal>::opt_cast_from(0.375).unwrap(), Decimal::new(375, 3));
+/// assert!(Exact::<Decimal>::opt_cast_from(0.1).is_none());
+/// assert!(Exact::<Decimal>::opt_cast_from(f64::NAN).is_none());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Exact<T>(pub T);
+
+impl<T> Exact<T> {
+    /// Return the number.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Exact<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+macro_rules! exact_int {
+    ($t:ty, $from:ident, $to:ident) => {
+        impl TryCastFrom<$t> for Exact<Decimal> {
+            fn can_cast_from(value: &$t) -> bool {
+                Decimal::$from(*value).is_some()
+            }
+
+            fn opt_cast_from(value: $t) -> Option<Self> {
+                Decimal::$from(value).map(Self)
+            }
+        }
+
+        impl TryCastFrom<Decimal> for Exact<$t> {
+            fn can_cast_from(value: &Decimal) -> bool {
+                value.fract().is_zero() && value.$to().is_some()
+            }
+
+            fn opt_cast_from(value: Decimal) -> Option<Self> {
+                if value.fract().is_zero() {
+                    value.$to().map(Self)
+                } else {
+                    None
+                }
+            }
+        }
+    };
+}
+
+exact_int!(u8, from_u8, to_u8);
+exact_int!(u16, from_u16, to_u16);
+exact_int!(u32, from_u32, to_u32);
+exact_int!(u64, from_u64, to_u64);
+exact_int!(u128, from_u128, to_u128);
+exact_int!(usize, from_usize, to_usize);
+exact_int!(i8, from_i8, to_i8);
+exact_int!(i16, from_i16, to_i16);
+exact_int!(i32, from_i32, to_i32);
+exact_int!(i64, from_i64, to_i64);
+exact_int!(i128, from_i128, to_i128);
+exact_int!(isize, from_isize, to_isize);
+
+/// The exact value of the finite float `mantissa * 2^exponent` as a [`Decimal`], if it fits.
+fn exact_decimal(negative: bool, mantissa: u64, exponent: i32) -> Option<Decimal> {
+    if mantissa == 0 {
+        return Some(Decimal::ZERO);
+    }
+
+    let zeros = mantissa.trailing_zeros();
+    let mantissa = u128::from(mantissa >> zeros);
+    let exponent = exponent + zeros as i32;
+
+    // 2^-k = 5^k / 10^k, so a float with a negative exponent has exactly k decimal places
+    let (mantissa, scale) = if exponent >= 0 {
+        (
+            mantissa.checked_mul(1u128.checked_shl(exponent as u32)?)?,
+            0,
+        )
+    } else if -exponent <= 28 {
+        (mantissa * 5u128.pow(-exponent as u32), -exponent as u32)
+    } else {
+        return None;
+    };
+
+    let mantissa = i128::try_from(mantissa).ok()?;
+    let mantissa = if negative { -mantissa } else { mantissa };
+    Decimal::try_from_i128_with_scale(mantissa, scale).ok()
+}
+
+// a float is cast into its exact binary value, which fails if it has more than 28 decimal places
+// (like 0.1, whose exact value is 0.1000000000000000055511151231257827...) or more than 96 bits
+macro_rules! exact_float {
+    ($t:ty, $exponent_bits:expr, $mantissa_bits:expr, $to:ident) => {
+        impl Exact<$t> {
+            fn decimal(value: $t) -> Option<Decimal> {
+                const MAX_EXPONENT: i32 = (1 << $exponent_bits) - 1;
+                const BIAS: i32 = MAX_EXPONENT / 2 + $mantissa_bits;
+
+                let bits = value.to_bits();
+                let fraction = bits & ((1 << $mantissa_bits) - 1);
+                let exponent = ((bits >> $mantissa_bits) as i32) & MAX_EXPONENT;
+
+                let (mantissa, exponent) = match exponent {
+                    MAX_EXPONENT => return None,
+                    0 => (fraction, 1 - BIAS),
+                    exponent => (fraction | (1 << $mantissa_bits), exponent - BIAS),
+                };
+
+                exact_decimal(value.is_sign_negative(), mantissa.into(), exponent)
+            }
+        }
+
+        impl TryCastFrom<$t> for Exact<Decimal> {
+            fn can_cast_from(value: &$t) -> bool {
+                Exact::<$t>::decimal(*value).is_some()
+            }
+
+            fn opt_cast_from(value: $t) -> Option<Self> {
+                Exact::<$t>::decimal(value).map(Self)
+            }
+        }
+
+        impl TryCastFrom<Decimal> for Exact<$t> {
+            fn can_cast_from(value: &Decimal) -> bool {
+                Self::opt_cast_from(*value).is_some()
+            }
+
+            fn opt_cast_from(value: Decimal) -> Option<Self> {
+                let float = value.$to()?;
+
+                if Self::decimal(float) == Some(value) {
+                    Some(Self(float))
+                } else {
+                    None
+                }
+            }
+        }
+    };
+}
+
+exact_float!(f32, 8, 23, to_f32);
+exact_float!(f64, 11, 52, to_f64);
+
+impl<'a> TryCastFrom<&'a str> for Exact<Decimal> {
+    fn can_cast_from(value: &&'a str) -> bool {
+        Decimal::from_str_exact(value).is_ok()
+    }
+
+    fn opt_cast_from(value: &'a str) -> Option<Self> {
+        Decimal::from_str_exact(value).ok().map(Self)
+    }
+}
+
+impl TryCastFrom<String> for Exact<Decimal> {
+    fn can_cast_from(value: &String) -> bool {
+        Decimal::from_str_exact(value).is_ok()
+    }
+
+    fn opt_cast_from(value: String) -> Option<Self> {
+        Decimal::from_str_exact(&value).ok().map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Match;
+
+    #[test]
+    fn test_exact_int() {
+        assert!(!u128::MAX.matches::<Exact<Decimal>>());
+        assert!(u64::MAX.matches::<Exact<Decimal>>());
+
+        let big = Decimal::from(u64::MAX) + Decimal::ONE;
+        assert!(!big.matches::<Exact<u64>>());
+        assert!(big.matches::<Exact<i128>>());
+        assert!(!Decimal::new(-1, 0).matches::<Exact<u8>>());
+        assert_eq!(
+            Exact::<i8>::opt_cast_from(Decimal::new(-10, 1)),
+            Some(Exact(-1))
+        );
+    }
+
+    #[test]
+    fn test_exact_float() {
+        assert!(!Decimal::new(1, 1).matches::<Exact<f64>>());
+        assert!(Decimal::new(5, 1).matches::<Exact<f32>>());
+        assert!(Decimal::new(-1025, 2).matches::<Exact<f64>>());
+        assert!(!Decimal::from_str_exact("0.1000000000000000055511151231")
+            .unwrap()
+            .matches::<Exact<f64>>());
+
+        assert_eq!(
+            *Exact::<Decimal>::opt_cast_from(-2.5f32).unwrap(),
+            Decimal::new(-25, 1)
+        );
+        assert_eq!(
+            Exact::<Decimal>::opt_cast_from(2f64.powi(-28))
+                .unwrap()
+                .scale(),
+            28
+        );
+        assert!(!2f64.powi(-29).matches::<Exact<Decimal>>());
+        assert_eq!(
+            *Exact::<Decimal>::opt_cast_from(-0.0).unwrap(),
+            Decimal::ZERO
+        );
+        assert_eq!(
+            *Exact::<Decimal>::opt_cast_from(2f64.powi(95)).unwrap(),
+            Decimal::from(1u128 << 95)
+        );
+        assert!(!2f64.powi(96).matches::<Exact<Decimal>>());
+        assert!(!0.1f64.matches::<Exact<Decimal>>());
+        assert!(!f32::from_bits(1).matches::<Exact<Decimal>>());
+        assert!(!f64::INFINITY.matches::<Exact<Decimal>>());
+        assert!(!1e30f64.matches::<Exact<Decimal>>());
+    }
+
+    #[test]
+    fn test_exact_str() {
+        assert!(Exact::<Decimal>::can_cast_from(&"1.50"));
+        assert!(!Exact::<Decimal>::can_cast_from(
+            &"1.0000000000000000000000000000001"
+        ));
+        assert!(!"one".to_string().matches::<Exact<Decimal>>());
+    }
+}
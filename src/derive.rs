@@ -0,0 +1,436 @@
+//! The [`AsType`], [`Cast`], [`Flag`], and [`VariantName`] derive macros, re-exported from
+//! `safecast-derive`.
+
+pub use safecast_derive::{AsType, Cast, Flag, VariantName};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CastError, CastFrom, Flags, Match, TryCastFrom, TryCastFromFields};
+
+    #[derive(Debug, PartialEq)]
+    struct Byte(u8);
+
+    impl TryCastFrom<u64> for Byte {
+        fn can_cast_from(value: &u64) -> bool {
+            *value <= u8::MAX as u64
+        }
+
+        fn opt_cast_from(value: u64) -> Option<Self> {
+            u8::try_from(value).ok().map(Self)
+        }
+    }
+
+    impl From<Byte> for u64 {
+        fn from(byte: Byte) -> Self {
+            byte.0 as u64
+        }
+    }
+
+    #[derive(Debug, PartialEq, VariantName)]
+    enum Token {
+        Int(u64),
+        Float(f64),
+        Word(String),
+        Range { start: u64, end: u64 },
+        Eof,
+    }
+
+    #[derive(Cast, Debug, PartialEq)]
+    #[cast(try_from = "Token", into = "Token")]
+    enum Literal {
+        Int(Byte),
+        Float(f64),
+        Range { start: u64, end: Byte },
+        Eof,
+    }
+
+    #[derive(Cast, Debug, PartialEq)]
+    #[cast(from = "Literal")]
+    enum Number {
+        Int(u64),
+        Float(f64),
+        Range { start: u64, end: u64 },
+        Eof,
+    }
+
+    #[test]
+    fn test_derive_subset_enum() {
+        assert_eq!(
+            Literal::opt_cast_from(Token::Int(1)),
+            Some(Literal::Int(Byte(1)))
+        );
+        assert_eq!(Literal::opt_cast_from(Token::Int(256)), None);
+        assert_eq!(Literal::opt_cast_from(Token::Eof), Some(Literal::Eof));
+        assert!(!Token::Word("a".into()).matches::<Literal>());
+        assert!(Token::Range { start: 1, end: 2 }.matches::<Literal>());
+        assert!(!Token::Range { start: 1, end: 256 }.matches::<Literal>());
+
+        let range = Literal::Range {
+            start: 1,
+            end: Byte(2),
+        };
+        assert_eq!(Token::cast_from(range), Token::Range { start: 1, end: 2 });
+    }
+
+    struct Point {
+        x: u64,
+        y: u64,
+    }
+
+    struct Segment {
+        start: Point,
+        end: Point,
+    }
+
+    #[derive(Cast)]
+    #[cast(try_from = "Segment")]
+    struct Span {
+        #[cast(from_field = "start.x")]
+        start: Byte,
+        #[cast(from_field = "end.x")]
+        end: Byte,
+    }
+
+    struct Line {
+        x: u64,
+        y: u64,
+        length: u64,
+    }
+
+    #[derive(Cast, Debug, PartialEq)]
+    #[cast(try_from = "&Line", aggregate_errors)]
+    struct Offset {
+        x: u64,
+        y: Byte,
+    }
+
+    #[derive(Cast, Debug, PartialEq)]
+    #[cast(try_from = "Line", aggregate_errors)]
+    struct Vector {
+        #[cast(flatten)]
+        offset: Offset,
+        length: Byte,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Coords(u64, u64);
+
+    #[derive(Cast, Debug, PartialEq)]
+    #[cast(from = "Coords", into = "Coords")]
+    struct Pair(u64, u64);
+
+    #[test]
+    fn test_derive_struct() {
+        let valid = Segment {
+            start: Point { x: 1, y: 2 },
+            end: Point { x: 3, y: 4 },
+        };
+
+        assert!(Span::can_cast_from(&valid));
+        let span = Span::opt_cast_from(valid).unwrap();
+        assert_eq!((span.start, span.end), (Byte(1), Byte(3)));
+
+        let invalid = Segment {
+            start: Point { x: 1, y: 2 },
+            end: Point { x: 256, y: 4 },
+        };
+
+        assert!(!Span::can_cast_from(&invalid));
+        assert_eq!(invalid.start.y + invalid.end.y, 6);
+
+        let pair = Pair::cast_from(Coords(1, 2));
+        assert_eq!(Coords::cast_from(pair), Coords(1, 2));
+    }
+
+    #[test]
+    fn test_derive_flatten() {
+        let line = Line {
+            x: 1,
+            y: 2,
+            length: 3,
+        };
+
+        assert!(line.matches::<Vector>());
+
+        let vector = Vector::opt_cast_from(line).unwrap();
+        assert_eq!(vector.offset, Offset { x: 1, y: Byte(2) });
+        assert_eq!(vector.length, Byte(3));
+
+        let line = Line {
+            x: 1,
+            y: 256,
+            length: 3,
+        };
+
+        assert!(!line.matches::<Vector>());
+        assert!(Vector::opt_cast_from(line).is_none());
+    }
+
+    #[test]
+    fn test_derive_aggregate_errors() {
+        let line = Line {
+            x: 1,
+            y: 256,
+            length: 300,
+        };
+
+        let errors = Vector::try_cast_from_fields(line).unwrap_err();
+        let errors: Vec<_> = errors.into_iter().collect();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].field(), "offset.y");
+        assert_eq!(errors[0].found(), "256");
+        assert_eq!(errors[1].field(), "length");
+        assert!(errors[1].expected().ends_with("Byte"));
+
+        let line = Line {
+            x: 1,
+            y: 2,
+            length: 3,
+        };
+
+        let vector = Vector::try_cast_from_fields(line).unwrap();
+        assert_eq!(vector.length, Byte(3));
+    }
+
+    #[test]
+    fn test_derive_aggregate_errors_flatten_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CASTS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Debug)]
+        struct Counted;
+
+        impl CastFrom<u64> for Counted {
+            fn cast_from(_: u64) -> Self {
+                CASTS.fetch_add(1, Ordering::Relaxed);
+                Self
+            }
+        }
+
+        #[derive(Cast)]
+        #[cast(try_from = "&Line", aggregate_errors)]
+        struct Inner {
+            x: Counted,
+        }
+
+        #[derive(Cast)]
+        #[cast(try_from = "Line", aggregate_errors)]
+        struct Outer {
+            #[cast(flatten)]
+            inner: Inner,
+            length: Byte,
+        }
+
+        let line = Line {
+            x: 1,
+            y: 2,
+            length: 3,
+        };
+
+        let outer = Outer::try_cast_from_fields(line).unwrap();
+        assert_eq!(outer.length, Byte(3));
+        assert!(matches!(outer.inner, Inner { x: Counted }));
+        assert_eq!(CASTS.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_derive_enum() {
+        assert_eq!(Number::cast_from(Literal::Int(Byte(2))), Number::Int(2));
+        assert_eq!(Number::cast_from(Literal::Float(0.5)), Number::Float(0.5));
+    }
+
+    #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Flag)]
+    #[repr(u16)]
+    enum Switch {
+        Verbose = 1,
+        Quiet = 2,
+        Force = 0x100,
+    }
+
+    #[test]
+    fn test_derive_flag() {
+        let flags: Flags<Switch> = [Switch::Verbose, Switch::Force].into_iter().collect();
+        assert_eq!(u16::cast_from(flags), 0x101);
+
+        let flags = Flags::<Switch>::opt_cast_from(0x102u16).unwrap();
+        assert!(flags.contains(&Switch::Quiet) && flags.contains(&Switch::Force));
+        assert!(!0x104u16.matches::<Flags<Switch>>());
+    }
+
+    #[derive(Clone)]
+    struct Tagged<T> {
+        tag: u64,
+        value: T,
+    }
+
+    #[derive(Cast, Debug, PartialEq)]
+    #[cast(try_from = "Tagged<T>", try_from = "&Tagged<T>", into = "Tagged<T>")]
+    #[cast(aggregate_errors)]
+    struct Small<T> {
+        tag: Byte,
+        value: T,
+    }
+
+    #[derive(Cast, Debug, PartialEq)]
+    #[cast(
+        try_from = "&Tagged<T>",
+        aggregate_errors,
+        bound = "T: Clone + std::fmt::Debug, U: TryCastFrom<T>"
+    )]
+    struct Converted<T, U> {
+        tag: Byte,
+        value: U,
+        #[cast(flatten)]
+        small: Small<T>,
+    }
+
+    #[derive(Cast, Debug, PartialEq)]
+    #[cast(try_from = "Tagged<u64>", into = "Tagged<u64>")]
+    struct Narrow<T> {
+        tag: u64,
+        value: T,
+    }
+
+    #[derive(Cast)]
+    #[cast(from = "&'a Line")]
+    struct Borrowed<'a> {
+        #[cast(from_field = "x")]
+        x: u64,
+        #[cast(flatten)]
+        line: Ref<'a>,
+    }
+
+    struct Ref<'a>(Option<&'a Line>);
+
+    impl<'a> CastFrom<&'a Line> for Ref<'a> {
+        fn cast_from(line: &'a Line) -> Self {
+            Self(Some(line))
+        }
+    }
+
+    #[derive(Cast)]
+    #[cast(try_from = "&'static Line")]
+    struct Static {
+        y: Byte,
+    }
+
+    #[derive(Cast, Debug, PartialEq)]
+    #[cast(from = "Number", bound = "T: CastFrom<u64>, U: CastFrom<f64>")]
+    enum Generic<T, U> {
+        Int(T),
+        Float(U),
+        Range { start: T, end: T },
+        Eof,
+    }
+
+    #[test]
+    fn test_derive_generic() {
+        let tagged = Tagged { tag: 1, value: "a" };
+        let small = Small::opt_cast_from(tagged.clone()).unwrap();
+        assert_eq!(small.tag, Byte(1));
+
+        let tagged: Tagged<&str> = Tagged::cast_from(small);
+        assert_eq!((tagged.tag, tagged.value), (1, "a"));
+
+        let tagged = Tagged { tag: 2, value: 3u8 };
+        let converted = Converted::<u8, u64>::opt_cast_from(&tagged).unwrap();
+        assert_eq!(converted.value, 3);
+        assert_eq!(converted.small.value, 3u8);
+
+        let tagged = Tagged {
+            tag: 256,
+            value: 3u8,
+        };
+        let errors = Converted::<u8, u64>::try_cast_from_fields(&tagged).unwrap_err();
+        let errors: Vec<_> = errors
+            .into_iter()
+            .map(|error| error.field().to_string())
+            .collect();
+        assert_eq!(errors, ["tag", "small.tag"]);
+
+        let line = Line {
+            x: 1,
+            y: 2,
+            length: 3,
+        };
+
+        let borrowed = Borrowed::cast_from(&line);
+        assert_eq!(borrowed.x, 1);
+        assert_eq!(borrowed.line.0.map(|line| line.length), Some(3));
+
+        static LINE: Line = Line {
+            x: 1,
+            y: 2,
+            length: 3,
+        };
+
+        assert_eq!(Static::opt_cast_from(&LINE).map(|s| s.y), Some(Byte(2)));
+
+        let generic = Generic::<u128, f64>::cast_from(Number::Range { start: 1, end: 2 });
+        assert_eq!(generic, Generic::Range { start: 1, end: 2 });
+    }
+
+    #[test]
+    fn test_derive_inferred_bounds() {
+        let tagged = Tagged {
+            tag: 1,
+            value: 2u64,
+        };
+        let narrow = Narrow::<Byte>::opt_cast_from(tagged).unwrap();
+        assert_eq!(narrow.value, Byte(2));
+
+        let tagged = Tagged::cast_from(narrow);
+        assert_eq!((tagged.tag, tagged.value), (1, 2));
+
+        let tagged = Tagged {
+            tag: 1,
+            value: 256u64,
+        };
+        assert!(!tagged.matches::<Narrow<Byte>>());
+
+        let errors = Small::<&str>::try_cast_from_fields(&Tagged {
+            tag: 256,
+            value: "a",
+        });
+        assert_eq!(errors.unwrap_err().into_iter().count(), 1);
+    }
+
+    #[derive(AsType, Clone, Debug, PartialEq)]
+    enum Cell<T> {
+        Value(T),
+        Range(Vec<T>),
+        Empty,
+    }
+
+    #[test]
+    fn test_derive_as_type() {
+        use crate::{arc_as_type_mut, AsType};
+        use std::sync::Arc;
+
+        let cell = Cell::from(vec![1u64, 2]);
+        assert_eq!(AsType::<Vec<u64>>::as_type(&cell), Some(&vec![1, 2]));
+        assert_eq!(AsType::<u64>::as_type(&cell), None);
+
+        let mut shared = Arc::new(Cell::Value(1u64));
+        *arc_as_type_mut::<u64, _>(&mut shared).unwrap() += 1;
+        assert_eq!(*shared, Cell::Value(2));
+
+        let mut empty = Cell::<u64>::Empty;
+        empty.get_or_insert_type(vec![3]).push(4);
+        assert_eq!(AsType::<Vec<u64>>::into_type(empty), Some(vec![3, 4]));
+    }
+
+    #[test]
+    fn test_derive_variant_name() {
+        use crate::VariantName;
+
+        assert_eq!(Token::Eof.variant_name(), "Eof");
+        assert_eq!(Token::Range { start: 1, end: 2 }.variant_name(), "Range");
+
+        let word = Token::Word("a".into());
+        let err = Literal::try_cast_from(word, CastError::of_variant::<_, Literal>).unwrap_err();
+        assert_eq!(err.source_variant(), Some("Word"));
+    }
+}
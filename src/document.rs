@@ -0,0 +1,361 @@
+//! A [`Decoded`] wrapper to cast a parsed [`Document`] value, such as a JSON `Value`, into a
+//! primitive, a string, a sequence, or a map, and the [`FormatCastError`] shared by the document
+//! format features.
+
+use std::convert::Infallible;
+use std::fmt;
+use std::ops::Deref;
+
+use super::{CastError, TryCastFrom};
+
+/// An error which occurs when a string can't be parsed as a document format, like JSON.
+pub trait ParseError: std::error::Error + 'static {
+    /// The name of the document format, like `"JSON"`.
+    const FORMAT: &'static str;
+}
+
+/// An error which occurs when a document cannot be cast into a target type.
+#[derive(Debug)]
+pub enum FormatCastError<E> {
+    /// The string is not a valid document.
+    Parse(E),
+    /// The string is a valid document, but its value cannot be cast into the target type.
+    Cast(CastError),
+}
+
+impl<E: ParseError> fmt::Display for FormatCastError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Parse(cause) => write!(f, "invalid {}: {}", E::FORMAT, cause),
+            Self::Cast(cause) => fmt::Display::fmt(cause, f),
+        }
+    }
+}
+
+impl<E: ParseError> std::error::Error for FormatCastError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(cause) => Some(cause),
+            Self::Cast(cause) => Some(cause),
+        }
+    }
+}
+
+impl<E> From<Infallible> for FormatCastError<E> {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+impl<E: ParseError> From<E> for FormatCastError<E> {
+    fn from(cause: E) -> Self {
+        Self::Parse(cause)
+    }
+}
+
+impl<E> From<CastError> for FormatCastError<E> {
+    fn from(cause: CastError) -> Self {
+        Self::Cast(cause)
+    }
+}
+
+/// Cast the result of parsing a document into `T`.
+pub(crate) fn cast_parsed<V, T, E>(parsed: Result<V, E>) -> Result<T, FormatCastError<E>>
+where
+    T: TryCastFrom<V>,
+    E: ParseError,
+{
+    T::try_cast_from(parsed?, |_| FormatCastError::Cast(CastError::new::<V, T>()))
+}
+
+/// A dynamically-typed value parsed from a document format, like JSON.
+///
+/// Each accessor returns `None` if this value is not of the requested kind.
+pub trait Document: Sized {
+    /// Borrow this value as a `bool`.
+    fn as_bool(&self) -> Option<bool>;
+
+    /// Borrow this value as a signed integer.
+    fn as_i64(&self) -> Option<i64>;
+
+    /// Borrow this value as an unsigned integer.
+    fn as_u64(&self) -> Option<u64>;
+
+    /// Borrow this value as a floating-point number.
+    fn as_f64(&self) -> Option<f64>;
+
+    /// Borrow this value as a string.
+    fn as_str(&self) -> Option<&str>;
+
+    /// Returns `true` if this value is null.
+    fn is_null(&self) -> bool;
+
+    /// Borrow the elements of this value, if it's a sequence.
+    fn as_seq(&self) -> Option<&[Self]>;
+
+    /// Return the elements of this value, if it's a sequence.
+    fn into_seq(self) -> Option<Vec<Self>>;
+
+    /// Borrow the entries of this value, if it's a map whose keys are all strings.
+    fn as_map(&self) -> Option<Vec<(&str, &Self)>>;
+
+    /// Return the entries of this value, if it's a map whose keys are all strings.
+    fn into_map(self) -> Option<Vec<(String, Self)>>;
+}
+
+/// A value cast from a parsed [`Document`].
+///
+/// Casting a document value like a `serde_json::Value` directly into a type like `u64` or
+/// `Vec<T>` would overlap with the blanket implementation of [`TryCastFrom`] for types which
+/// implement [`From`] (since the library which defines the value type could implement `From`),
+/// so this explicit wrapper is used instead. It can be cast from any [`Document`] into a `bool`,
+/// an integer or float which holds the number exactly, a `String`, an `Option<T>` (from null or
+/// a `T`), a `Vec<T>`, or a `HashMap` or `BTreeMap` with `String` keys, where `Decoded<T>` can
+/// itself be cast from the document. To cast a document into your own type, implement
+/// `TryCastFrom` for it directly, and to support your own document type, use the
+/// [`decoded!`](crate::decoded) macro.
+/// Example:
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use std::collections::BTreeMap;
+/// use safecast::{Decoded, TryCastFrom};
+/// use serde_json::json;
+///
+/// let ports = Decoded::<Vec<u16>>::opt_cast_from(json!([80, 443])).unwrap();
+/// assert_eq!(ports.into_inner(), [80, 443]);
+///
+/// assert!(!Decoded::<Vec<u16>>::can_cast_from(&json!([80, 65536])));
+///
+/// let ids = json!({"a": 1, "b": null});
+/// let ids = Decoded::<BTreeMap<String, Option<u64>>>::opt_cast_from(ids).unwrap();
+/// assert_eq!(ids["a"], Some(1));
+/// assert_eq!(ids["b"], None);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Decoded<T>(pub T);
+
+impl<T> Decoded<T> {
+    /// Return the decoded value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Decoded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[doc(hidden)]
+pub fn decode_int<V: Document, T: TryFrom<u64> + TryFrom<i64>>(value: &V) -> Option<T> {
+    match value.as_u64() {
+        Some(n) => T::try_from(n).ok(),
+        None => value.as_i64().and_then(|n| T::try_from(n).ok()),
+    }
+}
+
+#[doc(hidden)]
+pub fn decode_f32<V: Document>(value: &V) -> Option<f32> {
+    if let Some(n) = value.as_u64() {
+        let float = n as f32;
+        (float as u128 == u128::from(n)).then_some(float)
+    } else if let Some(n) = value.as_i64() {
+        let float = n as f32;
+        (float as i128 == i128::from(n)).then_some(float)
+    } else {
+        let n = value.as_f64()?;
+        let narrow = n as f32;
+        (f64::from(narrow) == n).then_some(narrow)
+    }
+}
+
+#[doc(hidden)]
+pub fn decode_f64<V: Document>(value: &V) -> Option<f64> {
+    if let Some(n) = value.as_u64() {
+        let float = n as f64;
+        (float as u128 == u128::from(n)).then_some(float)
+    } else if let Some(n) = value.as_i64() {
+        let float = n as f64;
+        (float as i128 == i128::from(n)).then_some(float)
+    } else {
+        value.as_f64()
+    }
+}
+
+#[doc(hidden)]
+pub fn can_decode_seq<V: Document, T>(value: &V) -> bool
+where
+    Decoded<T>: TryCastFrom<V>,
+{
+    match value.as_seq() {
+        Some(items) => items.iter().all(Decoded::<T>::can_cast_from),
+        None => false,
+    }
+}
+
+#[doc(hidden)]
+pub fn decode_seq<V: Document, T>(value: V) -> Option<Vec<T>>
+where
+    Decoded<T>: TryCastFrom<V>,
+{
+    value
+        .into_seq()?
+        .into_iter()
+        .map(|item| Decoded::<T>::opt_cast_from(item).map(Decoded::into_inner))
+        .collect()
+}
+
+#[doc(hidden)]
+pub fn can_decode_map<V: Document, T>(value: &V) -> bool
+where
+    Decoded<T>: TryCastFrom<V>,
+{
+    match value.as_map() {
+        Some(entries) => entries
+            .into_iter()
+            .all(|(_, value)| Decoded::<T>::can_cast_from(value)),
+        None => false,
+    }
+}
+
+#[doc(hidden)]
+pub fn decode_map<V: Document, T, M>(value: V) -> Option<M>
+where
+    Decoded<T>: TryCastFrom<V>,
+    M: FromIterator<(String, T)>,
+{
+    value
+        .into_map()?
+        .into_iter()
+        .map(|(key, value)| Some((key, Decoded::<T>::opt_cast_from(value)?.0)))
+        .collect()
+}
+
+/// Implement `TryCastFrom<V>` for each [`Decoded`](crate::Decoded) primitive, string, `Option`,
+/// `Vec`, and map type, for a concrete [`Document`](crate::Document) type `V`.
+///
+/// The implementations can't be generic over every `Document`, since they would overlap with the
+/// blanket implementation of `TryCastFrom` when `V` is itself a `Decoded` type. This crate calls
+/// this macro for the `Value` type of each of its document format features. Example:
+/// ```
+/// use safecast::{decoded, Decoded, Document, TryCastFrom};
+///
+/// struct Flag(bool);
+///
+/// impl Document for Flag {
+///     fn as_bool(&self) -> Option<bool> {
+///         Some(self.0)
+///     }
+///
+///     # fn as_i64(&self) -> Option<i64> { None }
+///     # fn as_u64(&self) -> Option<u64> { None }
+///     # fn as_f64(&self) -> Option<f64> { None }
+///     # fn as_str(&self) -> Option<&str> { None }
+///     # fn is_null(&self) -> bool { false }
+///     # fn as_seq(&self) -> Option<&[Self]> { None }
+///     # fn into_seq(self) -> Option<Vec<Self>> { None }
+///     # fn as_map(&self) -> Option<Vec<(&str, &Self)>> { None }
+///     # fn into_map(self) -> Option<Vec<(String, Self)>> { None }
+///     // ...
+/// }
+///
+/// decoded!(Flag);
+///
+/// assert_eq!(Decoded::<bool>::opt_cast_from(Flag(true)), Some(Decoded(true)));
+/// assert!(!Decoded::<u8>::can_cast_from(&Flag(true)));
+/// ```
+#[macro_export]
+macro_rules! decoded {
+    (@impl $v:ty; $($t:ty => |$value:ident| $decode:expr),*) => {
+        $(
+            impl $crate::TryCastFrom<$v> for $crate::Decoded<$t> {
+                fn can_cast_from($value: &$v) -> bool {
+                    $decode.is_some()
+                }
+
+                fn opt_cast_from(value: $v) -> Option<Self> {
+                    let $value = &value;
+                    $decode.map(Self)
+                }
+            }
+        )*
+    };
+    (@int $v:ty; $($t:ty),*) => {
+        $crate::decoded!(@impl $v; $($t => |value| $crate::__decode_int::<$v, $t>(value)),*);
+    };
+    ($v:ty) => {
+        $crate::decoded!(@int $v; u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+        $crate::decoded!(@impl $v;
+            bool => |value| $crate::Document::as_bool(value),
+            f32 => |value| $crate::__decode_f32(value),
+            f64 => |value| $crate::__decode_f64(value),
+            String => |value| $crate::Document::as_str(value).map(String::from)
+        );
+
+        impl<T> $crate::TryCastFrom<$v> for $crate::Decoded<Option<T>>
+        where
+            $crate::Decoded<T>: $crate::TryCastFrom<$v>,
+        {
+            fn can_cast_from(value: &$v) -> bool {
+                $crate::Document::is_null(value)
+                    || <$crate::Decoded<T> as $crate::TryCastFrom<$v>>::can_cast_from(value)
+            }
+
+            fn opt_cast_from(value: $v) -> Option<Self> {
+                if $crate::Document::is_null(&value) {
+                    Some(Self(None))
+                } else {
+                    <$crate::Decoded<T> as $crate::TryCastFrom<$v>>::opt_cast_from(value)
+                        .map(|item| Self(Some(item.0)))
+                }
+            }
+        }
+
+        impl<T> $crate::TryCastFrom<$v> for $crate::Decoded<Vec<T>>
+        where
+            $crate::Decoded<T>: $crate::TryCastFrom<$v>,
+        {
+            fn can_cast_from(value: &$v) -> bool {
+                $crate::__can_decode_seq::<$v, T>(value)
+            }
+
+            fn opt_cast_from(value: $v) -> Option<Self> {
+                $crate::__decode_seq::<$v, T>(value).map(Self)
+            }
+        }
+
+        impl<T, S> $crate::TryCastFrom<$v>
+            for $crate::Decoded<::std::collections::HashMap<String, T, S>>
+        where
+            $crate::Decoded<T>: $crate::TryCastFrom<$v>,
+            S: ::std::hash::BuildHasher + Default,
+        {
+            fn can_cast_from(value: &$v) -> bool {
+                $crate::__can_decode_map::<$v, T>(value)
+            }
+
+            fn opt_cast_from(value: $v) -> Option<Self> {
+                $crate::__decode_map::<$v, T, _>(value).map(Self)
+            }
+        }
+
+        impl<T> $crate::TryCastFrom<$v>
+            for $crate::Decoded<::std::collections::BTreeMap<String, T>>
+        where
+            $crate::Decoded<T>: $crate::TryCastFrom<$v>,
+        {
+            fn can_cast_from(value: &$v) -> bool {
+                $crate::__can_decode_map::<$v, T>(value)
+            }
+
+            fn opt_cast_from(value: $v) -> Option<Self> {
+                $crate::__decode_map::<$v, T, _>(value).map(Self)
+            }
+        }
+    };
+}
@@ -0,0 +1,152 @@
+//! A [`CastDrain`] extension trait to cast a large `Vec` without holding both the source and the
+//! result in memory at full size.
+
+#[cfg(feature = "allocator_api")]
+use std::alloc::Allocator;
+
+use super::{CastFrom, TryCastFrom};
+
+/// The number of chunks in which a `Vec` is drained.
+const CHUNKS: usize = 16;
+
+/// Blanket implementation of methods to cast each element of a `Vec` while draining it,
+/// releasing the memory of the source as the result grows. Do not implement this trait.
+/// With the `allocator_api` feature, the result is allocated with a clone of the allocator of
+/// the source.
+///
+/// Collecting `vec.into_iter().map(U::cast_from)` holds the whole source allocation alive until
+/// the last element has been cast, so when `U` is larger than `T` the peak memory use is the sum
+/// of the sizes of both vectors. These methods instead move elements out of the source one chunk
+/// (a sixteenth of its length) at a time, shrinking the source and growing the result by exactly
+/// one chunk each time, so that the peak memory use is about one chunk more than the larger of
+/// the two. The cost is a reallocation of each vector per chunk, which most allocators can do in
+/// place for a large allocation. Since the source shrinks from its end, its elements are cast
+/// in reverse order, from last to first; the result is in the original order.
+///
+/// Example:
+/// ```
+/// use safecast::CastDrain;
+///
+/// let samples: Vec<u8> = (0..=255).collect();
+/// let wide: Vec<u64> = samples.cast_drain();
+/// assert_eq!(wide.len(), 256);
+/// assert_eq!(wide[255], 255);
+///
+/// let fields = vec!["1", "2", "x"];
+/// assert!(fields.try_cast_drain::<safecast::Parsed<u8>>().is_none());
+/// ```
+pub trait CastDrain<T> {
+    /// A `Vec` of `U`, with the same allocator as this `Vec`.
+    type Drained<U>;
+
+    /// Cast each element into `U`, draining this `Vec` as the result grows.
+    fn cast_drain<U: CastFrom<T>>(self) -> Self::Drained<U>;
+
+    /// Attempt to cast each element into `U`, draining this `Vec` as the result grows.
+    /// Elements are cast from last to first, so this stops at the *last* element which can't be
+    /// cast, dropping the rest of the source (the elements before it).
+    fn try_cast_drain<U: TryCastFrom<T>>(self) -> Option<Self::Drained<U>>;
+}
+
+macro_rules! cast_drain {
+    ([$($g:tt)*] $vec:ty => $drained:ty, |$source:ident| $new:expr) => {
+        impl<T, $($g)*> CastDrain<T> for $vec {
+            type Drained<U> = $drained;
+
+            fn cast_drain<U: CastFrom<T>>(self) -> $drained {
+                drain(self, |item| Some(U::cast_from(item))).expect("cast")
+            }
+
+            fn try_cast_drain<U: TryCastFrom<T>>(self) -> Option<$drained> {
+                drain(self, U::opt_cast_from)
+            }
+        }
+
+        fn drain<T, U, C, $($g)*>(mut $source: $vec, mut cast: C) -> Option<$drained>
+        where
+            C: FnMut(T) -> Option<U>,
+        {
+            let chunk_size = $source.len().div_ceil(CHUNKS).max(1);
+            let mut cast_chunks = $new;
+
+            // elements are drained from the end, so the result is built in reverse order
+            while !$source.is_empty() {
+                let start = $source.len().saturating_sub(chunk_size);
+                cast_chunks.reserve_exact($source.len() - start);
+
+                for item in $source.drain(start..).rev() {
+                    cast_chunks.push(cast(item)?);
+                }
+
+                $source.shrink_to_fit();
+            }
+
+            cast_chunks.reverse();
+            Some(cast_chunks)
+        }
+    };
+}
+
+#[cfg(not(feature = "allocator_api"))]
+cast_drain!([] Vec<T> => Vec<U>, |source| Vec::new());
+#[cfg(feature = "allocator_api")]
+cast_drain!([A: Allocator + Clone] Vec<T, A> => Vec<U, A>, |source| {
+    Vec::new_in(source.allocator().clone())
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parsed;
+
+    #[test]
+    fn test_cast_drain() {
+        let source: Vec<u16> = (0..1000).collect();
+        let cast: Vec<u64> = source.cast_drain();
+        assert_eq!(cast, (0..1000).collect::<Vec<u64>>());
+
+        assert!(Vec::<u8>::new().cast_drain::<u64>().is_empty());
+
+        let fields: Vec<String> = (0..100).map(|n| n.to_string()).collect();
+        let parsed = fields.try_cast_drain::<Parsed<u8>>().unwrap();
+        assert_eq!(parsed[42], Parsed(42));
+
+        let fields: Vec<String> = (250..300).map(|n| n.to_string()).collect();
+        assert!(fields.try_cast_drain::<Parsed<u8>>().is_none());
+    }
+
+    #[test]
+    fn test_try_cast_drain_order() {
+        use std::sync::Mutex;
+
+        static CALLS: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+        struct Small;
+
+        impl TryCastFrom<u8> for Small {
+            fn can_cast_from(value: &u8) -> bool {
+                *value < 10
+            }
+
+            fn opt_cast_from(value: u8) -> Option<Self> {
+                CALLS.lock().unwrap().push(value);
+                Self::can_cast_from(&value).then_some(Self)
+            }
+        }
+
+        assert!(vec![1u8, 20, 3, 30, 5].try_cast_drain::<Small>().is_none());
+        assert_eq!(*CALLS.lock().unwrap(), [5, 30]);
+    }
+
+    #[cfg(feature = "allocator_api")]
+    #[test]
+    fn test_cast_drain_allocator() {
+        use std::alloc::System;
+
+        let mut source = Vec::new_in(System);
+        source.extend(0..100u16);
+
+        let cast: Vec<u64, System> = source.cast_drain();
+        assert_eq!(cast, (0..100).collect::<Vec<u64>>());
+    }
+}
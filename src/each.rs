@@ -0,0 +1,255 @@
+//! An [`Each`] wrapper to cast a sequence element-wise into another kind of sequence.
+
+#[cfg(feature = "allocator_api")]
+use std::alloc::Allocator;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use super::{expect_cast, CastBudget, TryCastFrom, TryCastFromBounded};
+
+/// A sequence each of whose elements was cast from an element of a source sequence.
+///
+/// Casting element-wise between `Vec<T>`, `Box<[T]>`, `Arc<[T]>`, and arrays directly would overlap
+/// with the blanket implementation of [`TryCastFrom`] for types which implement [`From`]
+/// (in fact the standard library already provides `From<Vec<T>> for Arc<[T]>`), so this explicit
+/// wrapper is used instead. An `Arc<[T]>` source requires `T: Clone`, since its elements are
+/// shared. When the element types are the same, no wrapper is needed; cast directly, e.g. with
+/// `Arc::<[T]>::cast_from(vec)`.
+/// Example:
+/// ```
+/// use std::sync::Arc;
+/// use safecast::{Each, TryCastFrom, TryCastInto};
+///
+/// let shared = Each::<Arc<[u64]>>::opt_cast_from(vec![1u8, 2, 3]).unwrap();
+/// assert_eq!(&shared[..], [1, 2, 3]);
+///
+/// let names = Arc::<[&str]>::from(["a", "b"]);
+/// let names: Each<Vec<String>> = names.opt_cast_into().unwrap();
+/// assert_eq!(names.into_inner(), ["a", "b"]);
+/// ```
+///
+/// There is no separate SIMD cast. `Each` checks every element, which can keep the compiler from
+/// vectorizing its loop, so for a bulk numeric cast which can't fail, like widening `u16` samples
+/// into `u32`, collect [`CastIter::cast_each`](crate::CastIter::cast_each) instead; the compiler
+/// auto-vectorizes it.
+///
+/// Casting an array does not allocate. Nested sequences, like a matrix `[[T; N]; M]` or a
+/// `Vec<[T; N]>`, are cast by nesting `Each`, then unwrapped with `into_nested`:
+/// ```
+/// use safecast::{Each, TryCastFrom};
+///
+/// let matrix = [[1u8, 2], [3, 4], [5, 6]];
+/// let matrix = Each::<[Each<[u32; 2]>; 3]>::opt_cast_from(matrix).unwrap();
+/// assert_eq!(matrix.into_nested(), [[1u32, 2], [3, 4], [5, 6]]);
+///
+/// let rows = vec![["1", "2"], ["3", "x"]];
+/// assert!(!Each::<Vec<Each<[safecast::Parsed<u8>; 2]>>>::can_cast_from(&rows));
+/// ```
+///
+/// With the `allocator_api` feature, an `Each<Vec<U, A>>` or `Each<Box<[U], A>>` cast from a
+/// `Vec<T, A>` is allocated with a clone of its allocator `A`.
+///
+/// An `Each` also implements [`TryCastFromBounded`], counting each element against the budget
+/// one level of nesting deeper than the sequence itself.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Each<C>(pub C);
+
+impl<C> Each<C> {
+    /// Return the cast sequence.
+    pub fn into_inner(self) -> C {
+        self.0
+    }
+}
+
+impl<U, const N: usize, const M: usize> Each<[Each<[U; N]>; M]> {
+    /// Return the cast matrix.
+    pub fn into_nested(self) -> [[U; N]; M] {
+        self.0.map(Each::into_inner)
+    }
+}
+
+impl<U, const N: usize> Each<Vec<Each<[U; N]>>> {
+    /// Return the cast rows.
+    pub fn into_nested(self) -> Vec<[U; N]> {
+        self.0.into_iter().map(Each::into_inner).collect()
+    }
+}
+
+impl<C> Deref for Each<C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.0
+    }
+}
+
+macro_rules! each {
+    (@impl $bound:path, [$($g:tt)*]; $source:ty, |$value:ident| $cast:expr => $target:ty) => {
+        impl<T: $bound, U: TryCastFrom<T>, $($g)*> TryCastFrom<$source> for Each<$target> {
+            fn can_cast_from(value: &$source) -> bool {
+                value.iter().all(U::can_cast_from)
+            }
+
+            fn opt_cast_from($value: $source) -> Option<Self> {
+                $cast.map(Self)
+            }
+        }
+
+        impl<T: $bound, U: TryCastFromBounded<T>, $($g)*> TryCastFromBounded<$source>
+            for Each<$target>
+        {
+            fn can_cast_from_bounded(value: &$source, budget: &mut CastBudget) -> bool {
+                budget.all::<T, U, _>(value.iter())
+            }
+        }
+    };
+    ($bound:path, $g:tt; $source:ty, |$value:ident| $iter:expr => $($target:ty),*) => {
+        $(
+            each!(@impl $bound, $g; $source, |$value| {
+                $iter.map(U::opt_cast_from).collect::<Option<$target>>()
+            } => $target);
+        )*
+    };
+}
+
+#[cfg(not(feature = "allocator_api"))]
+each!(Sized, []; Vec<T>, |value| value.into_iter() => Vec<U>, Box<[U]>, Arc<[U]>);
+each!(Sized, []; Box<[T]>, |value| value.into_vec().into_iter() => Vec<U>, Box<[U]>, Arc<[U]>);
+each!(Clone, []; Arc<[T]>, |value| value.iter().cloned() => Vec<U>, Box<[U]>, Arc<[U]>);
+
+/// Cast each element of `source` into a new `Vec` in the same allocator.
+#[cfg(feature = "allocator_api")]
+fn cast_in<T, U: TryCastFrom<T>, A: Allocator + Clone>(source: Vec<T, A>) -> Option<Vec<U, A>> {
+    let mut cast = Vec::with_capacity_in(source.len(), source.allocator().clone());
+
+    for item in source {
+        cast.push(U::opt_cast_from(item)?);
+    }
+
+    Some(cast)
+}
+
+#[cfg(feature = "allocator_api")]
+each!(@impl Sized, [A: Allocator + Clone]; Vec<T, A>, |value| cast_in(value) => Vec<U, A>);
+#[cfg(feature = "allocator_api")]
+each!(@impl Sized, [A: Allocator + Clone]; Vec<T, A>, |value| {
+    cast_in(value).map(Vec::into_boxed_slice)
+} => Box<[U], A>);
+#[cfg(feature = "allocator_api")]
+each!(Sized, [A: Allocator]; Vec<T, A>, |value| value.into_iter() => Arc<[U]>);
+
+impl<T, U: TryCastFrom<T>, const N: usize> TryCastFrom<[T; N]> for Each<[U; N]> {
+    fn can_cast_from(value: &[T; N]) -> bool {
+        value.iter().all(U::can_cast_from)
+    }
+
+    fn opt_cast_from(value: [T; N]) -> Option<Self> {
+        let mut items = value.into_iter().map(U::opt_cast_from);
+        let mut failed = false;
+
+        // stop casting as soon as one element fails, dropping the rest
+        let cast: [Option<U>; N] = std::array::from_fn(|_| {
+            let item = if failed { None } else { items.next().flatten() };
+            failed = item.is_none();
+            item
+        });
+
+        if failed {
+            None
+        } else {
+            Some(Self(cast.map(expect_cast::<T, U>)))
+        }
+    }
+}
+
+impl<T, U: TryCastFromBounded<T>, const N: usize> TryCastFromBounded<[T; N]> for Each<[U; N]> {
+    fn can_cast_from_bounded(value: &[T; N], budget: &mut CastBudget) -> bool {
+        budget.all::<T, U, _>(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CastLimits, Match, Parsed};
+
+    #[test]
+    fn test_each() {
+        let boxed: Box<[&str]> = Box::new(["1", "2"]);
+        assert!(boxed.matches::<Each<Arc<[Parsed<u8>]>>>());
+
+        let cast = Each::<Vec<Parsed<u8>>>::opt_cast_from(boxed).unwrap();
+        assert_eq!(cast.into_inner(), [Parsed(1), Parsed(2)]);
+
+        let invalid: Arc<[&str]> = Arc::from(["1", "256"]);
+        assert!(!invalid.matches::<Each<Box<[Parsed<u8>]>>>());
+        assert!(Each::<Vec<Parsed<u8>>>::opt_cast_from(invalid).is_none());
+    }
+
+    #[test]
+    fn test_each_array() {
+        let cast = Each::<[Parsed<u8>; 3]>::opt_cast_from(["1", "2", "3"]).unwrap();
+        assert_eq!(cast.into_inner(), [Parsed(1), Parsed(2), Parsed(3)]);
+        assert!(!["1", "256"].matches::<Each<[Parsed<u8>; 2]>>());
+
+        let matrix = [[1u8, 2, 3], [4, 5, 6]];
+        let cast = Each::<[Each<[u64; 3]>; 2]>::opt_cast_from(matrix).unwrap();
+        assert_eq!(cast.into_nested(), [[1u64, 2, 3], [4, 5, 6]]);
+
+        let rows: Vec<[&str; 2]> = vec![["1", "2"], ["3", "4"]];
+        let cast = Each::<Vec<Each<[Parsed<u8>; 2]>>>::opt_cast_from(rows).unwrap();
+        assert_eq!(cast.into_nested()[1], [Parsed(3), Parsed(4)]);
+    }
+
+    #[test]
+    fn test_each_bounded() {
+        let matrix = vec![[1u8, 2, 3], [4, 5, 6]];
+        type Matrix = Each<Vec<Each<[u64; 3]>>>;
+
+        assert!(matrix.matches_bounded::<Matrix>(CastLimits::new(2, 9)));
+        assert!(!matrix.matches_bounded::<Matrix>(CastLimits::new(2, 8)));
+        assert!(!matrix.matches_bounded::<Matrix>(CastLimits::new(1, 9)));
+
+        let shared: Arc<[u8]> = Arc::from([1, 2]);
+        assert!(shared.matches_bounded::<Each<Box<[u64]>>>(CastLimits::new(1, 3)));
+        assert!(!shared.matches_bounded::<Each<Box<[u64]>>>(CastLimits::new(0, 3)));
+    }
+
+    #[test]
+    fn test_each_array_short_circuit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CASTS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Small;
+
+        impl TryCastFrom<u8> for Small {
+            fn can_cast_from(value: &u8) -> bool {
+                *value < 10
+            }
+
+            fn opt_cast_from(value: u8) -> Option<Self> {
+                CASTS.fetch_add(1, Ordering::Relaxed);
+                Self::can_cast_from(&value).then_some(Self)
+            }
+        }
+
+        assert!(Each::<[Small; 4]>::opt_cast_from([1u8, 20, 3, 4]).is_none());
+        assert_eq!(CASTS.load(Ordering::Relaxed), 2);
+    }
+
+    #[cfg(feature = "allocator_api")]
+    #[test]
+    fn test_each_allocator() {
+        use std::alloc::System;
+
+        let mut source = Vec::new_in(System);
+        source.extend([1u8, 2, 3]);
+
+        let cast: Vec<u64, System> = Each::<Vec<u64, System>>::opt_cast_from(source)
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(cast, [1, 2, 3]);
+    }
+}
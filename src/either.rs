@@ -0,0 +1,172 @@
+//! Casting with [`Either`](::either::Either), using [`either`](::either).
+
+use std::ops::Deref;
+
+use ::either::Either::{self, Left, Right};
+
+use super::{AsType, CastFrom, TryCastFrom};
+
+/// An [`Either`] which implements [`AsType<L>`](AsType) for its left type `L`.
+///
+/// Like [`OkType`](crate::OkType) for a [`Result`], this wrapper is needed because `Either<L, R>`
+/// doesn't implement `From<L>` and can't implement both `AsType<L>` and `AsType<R>`, which would
+/// overlap when `L` and `R` are the same type. Because the wrapper implements `From<L>`, it can
+/// also be cast from an `L`.
+/// Example:
+/// ```
+/// use either::Either;
+/// use safecast::{AsType, CastFrom, LeftType};
+///
+/// let mut value = LeftType::<u64, String>::cast_from(1);
+/// *value.as_type_mut().unwrap() += 1;
+/// assert_eq!(value.into_inner(), Either::Left(2));
+///
+/// let value = LeftType::<u64, String>(Either::Right("a".to_string()));
+/// assert_eq!(value.as_type(), None);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LeftType<L, R>(pub Either<L, R>);
+
+/// An [`Either`] which implements [`AsType<R>`](AsType) for its right type `R`.
+/// See [`LeftType`] for details.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct RightType<L, R>(pub Either<L, R>);
+
+macro_rules! either_type {
+    ($wrapper:ident, $variant:ident, $t:ident) => {
+        impl<L, R> $wrapper<L, R> {
+            /// Return the wrapped [`Either`].
+            pub fn into_inner(self) -> Either<L, R> {
+                self.0
+            }
+        }
+
+        impl<L, R> Deref for $wrapper<L, R> {
+            type Target = Either<L, R>;
+
+            fn deref(&self) -> &Either<L, R> {
+                &self.0
+            }
+        }
+
+        impl<L, R> From<$wrapper<L, R>> for Either<L, R> {
+            fn from(either: $wrapper<L, R>) -> Self {
+                either.0
+            }
+        }
+
+        impl<L, R> From<$t> for $wrapper<L, R> {
+            fn from(value: $t) -> Self {
+                Self($variant(value))
+            }
+        }
+
+        impl<L, R> AsType<$t> for $wrapper<L, R> {
+            fn as_type(&self) -> Option<&$t> {
+                match &self.0 {
+                    $variant(value) => Some(value),
+                    _ => None,
+                }
+            }
+
+            fn as_type_mut(&mut self) -> Option<&mut $t> {
+                match &mut self.0 {
+                    $variant(value) => Some(value),
+                    _ => None,
+                }
+            }
+
+            fn into_type(self) -> Option<$t> {
+                match self.0 {
+                    $variant(value) => Some(value),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+either_type!(LeftType, Left, L);
+either_type!(RightType, Right, R);
+
+/// Methods to cast an [`Either`] into a type which can be cast from both of its sides.
+/// Do not implement this trait.
+///
+/// A `TryCastFrom<Either<L, R>>` implementation for every such `T` would overlap with the blanket
+/// implementation for types which implement [`From`], so these methods are provided instead.
+/// Example:
+/// ```
+/// use either::Either;
+/// use safecast::{CastEither, Parsed};
+///
+/// let value: Either<u8, u32> = Either::Left(1);
+/// assert_eq!(value.cast_either::<u64>(), 1);
+///
+/// let value: Either<&str, String> = Either::Right("256".to_string());
+/// assert!(!value.can_cast_either::<Parsed<u8>>());
+/// assert_eq!(value.opt_cast_either::<Parsed<u16>>(), Some(Parsed(256)));
+/// ```
+pub trait CastEither<L, R> {
+    /// Cast this value into `T`.
+    fn cast_either<T: CastFrom<L> + CastFrom<R>>(self) -> T;
+
+    /// Returns `true` if this value can be cast into `T`.
+    fn can_cast_either<T: TryCastFrom<L> + TryCastFrom<R>>(&self) -> bool;
+
+    /// Cast this value into `T`, if possible.
+    fn opt_cast_either<T: TryCastFrom<L> + TryCastFrom<R>>(self) -> Option<T>;
+}
+
+impl<L, R> CastEither<L, R> for Either<L, R> {
+    fn cast_either<T: CastFrom<L> + CastFrom<R>>(self) -> T {
+        match self {
+            Left(left) => T::cast_from(left),
+            Right(right) => T::cast_from(right),
+        }
+    }
+
+    fn can_cast_either<T: TryCastFrom<L> + TryCastFrom<R>>(&self) -> bool {
+        match self {
+            Left(left) => T::can_cast_from(left),
+            Right(right) => T::can_cast_from(right),
+        }
+    }
+
+    fn opt_cast_either<T: TryCastFrom<L> + TryCastFrom<R>>(self) -> Option<T> {
+        match self {
+            Left(left) => T::opt_cast_from(left),
+            Right(right) => T::opt_cast_from(right),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parsed;
+
+    #[test]
+    fn test_either_type() {
+        let mut value = RightType::<u8, u8>(Left(1));
+        assert_eq!(value.as_type(), None);
+
+        *value.get_or_insert_type(2) += 1;
+        assert_eq!(value.into_inner(), Right(3));
+
+        let value = LeftType::<&str, u8>::from("a");
+        assert_eq!(value.into_type(), Some("a"));
+    }
+
+    #[test]
+    fn test_cast_either() {
+        let value: Either<&str, String> = Left("1");
+        assert!(value.can_cast_either::<Parsed<u8>>());
+        assert_eq!(value.opt_cast_either::<Parsed<u8>>(), Some(Parsed(1)));
+
+        let value: Either<&str, String> = Right("x".to_string());
+        assert_eq!(value.opt_cast_either::<Parsed<u8>>(), None);
+
+        let value: Either<u16, u32> = Right(2);
+        assert_eq!(value.cast_either::<u64>(), 2);
+    }
+}
@@ -0,0 +1,75 @@
+//! The [`CastError`] type which describes a failed cast.
+
+use std::any::type_name;
+use std::convert::Infallible;
+use std::fmt;
+
+use super::VariantName;
+
+/// An error which occurs when a value cannot be cast into a target type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CastError {
+    source_type: &'static str,
+    source_variant: Option<&'static str>,
+    target_type: &'static str,
+}
+
+impl CastError {
+    /// Construct a new error describing a failed cast from `F` into `T`.
+    pub fn new<F: ?Sized, T: ?Sized>() -> Self {
+        Self {
+            source_type: type_name::<F>(),
+            source_variant: None,
+            target_type: type_name::<T>(),
+        }
+    }
+
+    /// Construct a new error describing a failed cast of `value` into `T`, including the name of
+    /// its variant, as in "cannot cast Value::Map into u64".
+    pub fn of_variant<F: VariantName + ?Sized, T: ?Sized>(value: &F) -> Self {
+        Self {
+            source_variant: Some(value.variant_name()),
+            ..Self::new::<F, T>()
+        }
+    }
+
+    /// The name of the type which could not be cast.
+    pub fn source_type(&self) -> &'static str {
+        self.source_type
+    }
+
+    /// The name of the variant of the source type which could not be cast, if known.
+    pub fn source_variant(&self) -> Option<&'static str> {
+        self.source_variant
+    }
+
+    /// The name of the type which could not be cast into.
+    pub fn target_type(&self) -> &'static str {
+        self.target_type
+    }
+}
+
+impl fmt::Display for CastError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.source_variant {
+            Some(variant) => write!(
+                f,
+                "cannot cast {}::{} into {}",
+                self.source_type, variant, self.target_type
+            ),
+            None => write!(
+                f,
+                "cannot cast {} into {}",
+                self.source_type, self.target_type
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CastError {}
+
+impl From<Infallible> for CastError {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
@@ -0,0 +1,226 @@
+//! [`Prefix`] and [`Suffix`] wrappers to cast a leading or trailing subset of a tuple or `Vec`.
+//!
+//! Casting a long tuple directly into a shorter one would overlap with the blanket
+//! implementation of [`TryCastFrom`] for types which implement [`From`], so these explicit
+//! wrappers are used instead.
+
+#[cfg(feature = "allocator_api")]
+use std::alloc::Allocator;
+
+use super::TryCastFrom;
+
+/// The leading elements of a tuple or `Vec`, each cast into the corresponding element of `T`.
+///
+/// Example:
+/// ```
+/// use safecast::{Prefix, TryCastFrom};
+///
+/// let Prefix((a, b)) = Prefix::<(u64, String)>::opt_cast_from((1u32, "b", 'c')).unwrap();
+/// assert_eq!((a, b.as_str()), (1, "b"));
+///
+/// let args = vec![1u8, 2, 3];
+/// assert_eq!(Prefix::<(u16, u32)>::opt_cast_from(args), Some(Prefix((1, 2))));
+/// assert_eq!(Prefix::<(u16, u32)>::opt_cast_from(vec![1u8]), None);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Prefix<T>(pub T);
+
+impl<T> Prefix<T> {
+    /// Return the extracted elements.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// The trailing elements of a tuple or `Vec`, each cast into the corresponding element of `T`.
+///
+/// Example:
+/// ```
+/// use safecast::{Suffix, TryCastFrom};
+///
+/// assert_eq!(Suffix::<(char,)>::opt_cast_from((1u32, "b", 'c')), Some(Suffix(('c',))));
+/// assert_eq!(Suffix::<(u16, u32)>::opt_cast_from(vec![1u8, 2, 3]), Some(Suffix((2, 3))));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Suffix<T>(pub T);
+
+impl<T> Suffix<T> {
+    /// Return the extracted elements.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+macro_rules! prefix {
+    ($($t:ident: $u:ident => $i:tt),+ $(; $($r:ident),+)?) => {
+        impl<$($t, $u: TryCastFrom<$t>,)+ $($($r),+)?> TryCastFrom<($($t,)+ $($($r,)+)?)>
+            for Prefix<($($u,)+)>
+        {
+            fn can_cast_from(value: &($($t,)+ $($($r,)+)?)) -> bool {
+                $($u::can_cast_from(&value.$i))&&+
+            }
+
+            fn opt_cast_from(value: ($($t,)+ $($($r,)+)?)) -> Option<Self> {
+                Some(Self(($($u::opt_cast_from(value.$i)?,)+)))
+            }
+        }
+    };
+}
+
+macro_rules! suffix {
+    ($($r:ident),*; $($t:ident: $u:ident => $i:tt),+) => {
+        impl<$($r,)* $($t, $u: TryCastFrom<$t>),+> TryCastFrom<($($r,)* $($t,)+)>
+            for Suffix<($($u,)+)>
+        {
+            fn can_cast_from(value: &($($r,)* $($t,)+)) -> bool {
+                $($u::can_cast_from(&value.$i))&&+
+            }
+
+            fn opt_cast_from(value: ($($r,)* $($t,)+)) -> Option<Self> {
+                Some(Self(($($u::opt_cast_from(value.$i)?,)+)))
+            }
+        }
+    };
+}
+
+macro_rules! vec_prefix {
+    ($n:literal; $($u:ident => $i:tt),+) => {
+        #[cfg(not(feature = "allocator_api"))]
+        vec_prefix!(@impl [T] Vec<T>; $n; $($u => $i),+);
+        #[cfg(feature = "allocator_api")]
+        vec_prefix!(@impl [T, A: Allocator] Vec<T, A>; $n; $($u => $i),+);
+    };
+    (@impl [$($g:tt)*] $vec:ty; $n:literal; $($u:ident => $i:tt),+) => {
+        impl<$($g)*, $($u: TryCastFrom<T>),+> TryCastFrom<$vec> for Prefix<($($u,)+)> {
+            fn can_cast_from(value: &$vec) -> bool {
+                value.len() >= $n && $($u::can_cast_from(&value[$i]))&&+
+            }
+
+            fn opt_cast_from(value: $vec) -> Option<Self> {
+                let mut items = value.into_iter();
+                Some(Self(($($u::opt_cast_from(items.next()?)?,)+)))
+            }
+        }
+    };
+}
+
+macro_rules! vec_suffix {
+    ($n:literal; $($u:ident => $i:tt),+) => {
+        #[cfg(not(feature = "allocator_api"))]
+        vec_suffix!(@impl [T] Vec<T>; $n; $($u => $i),+);
+        #[cfg(feature = "allocator_api")]
+        vec_suffix!(@impl [T, A: Allocator] Vec<T, A>; $n; $($u => $i),+);
+    };
+    (@impl [$($g:tt)*] $vec:ty; $n:literal; $($u:ident => $i:tt),+) => {
+        impl<$($g)*, $($u: TryCastFrom<T>),+> TryCastFrom<$vec> for Suffix<($($u,)+)> {
+            fn can_cast_from(value: &$vec) -> bool {
+                if let Some(offset) = value.len().checked_sub($n) {
+                    $($u::can_cast_from(&value[offset + $i]))&&+
+                } else {
+                    false
+                }
+            }
+
+            fn opt_cast_from(value: $vec) -> Option<Self> {
+                let offset = value.len().checked_sub($n)?;
+                let mut items = value.into_iter().skip(offset);
+                Some(Self(($($u::opt_cast_from(items.next()?)?,)+)))
+            }
+        }
+    };
+}
+
+prefix!(T0: U0 => 0);
+prefix!(T0: U0 => 0; R1);
+prefix!(T0: U0 => 0, T1: U1 => 1);
+prefix!(T0: U0 => 0; R1, R2);
+prefix!(T0: U0 => 0, T1: U1 => 1; R2);
+prefix!(T0: U0 => 0, T1: U1 => 1, T2: U2 => 2);
+prefix!(T0: U0 => 0; R1, R2, R3);
+prefix!(T0: U0 => 0, T1: U1 => 1; R2, R3);
+prefix!(T0: U0 => 0, T1: U1 => 1, T2: U2 => 2; R3);
+prefix!(T0: U0 => 0, T1: U1 => 1, T2: U2 => 2, T3: U3 => 3);
+prefix!(T0: U0 => 0; R1, R2, R3, R4);
+prefix!(T0: U0 => 0, T1: U1 => 1; R2, R3, R4);
+prefix!(T0: U0 => 0, T1: U1 => 1, T2: U2 => 2; R3, R4);
+prefix!(T0: U0 => 0, T1: U1 => 1, T2: U2 => 2, T3: U3 => 3; R4);
+prefix!(T0: U0 => 0, T1: U1 => 1, T2: U2 => 2, T3: U3 => 3, T4: U4 => 4);
+prefix!(T0: U0 => 0; R1, R2, R3, R4, R5);
+prefix!(T0: U0 => 0, T1: U1 => 1; R2, R3, R4, R5);
+prefix!(T0: U0 => 0, T1: U1 => 1, T2: U2 => 2; R3, R4, R5);
+prefix!(T0: U0 => 0, T1: U1 => 1, T2: U2 => 2, T3: U3 => 3; R4, R5);
+prefix!(T0: U0 => 0, T1: U1 => 1, T2: U2 => 2, T3: U3 => 3, T4: U4 => 4; R5);
+prefix!(T0: U0 => 0, T1: U1 => 1, T2: U2 => 2, T3: U3 => 3, T4: U4 => 4, T5: U5 => 5);
+
+suffix!(; T0: U0 => 0);
+suffix!(R0; T1: U1 => 1);
+suffix!(; T0: U0 => 0, T1: U1 => 1);
+suffix!(R0, R1; T2: U2 => 2);
+suffix!(R0; T1: U1 => 1, T2: U2 => 2);
+suffix!(; T0: U0 => 0, T1: U1 => 1, T2: U2 => 2);
+suffix!(R0, R1, R2; T3: U3 => 3);
+suffix!(R0, R1; T2: U2 => 2, T3: U3 => 3);
+suffix!(R0; T1: U1 => 1, T2: U2 => 2, T3: U3 => 3);
+suffix!(; T0: U0 => 0, T1: U1 => 1, T2: U2 => 2, T3: U3 => 3);
+suffix!(R0, R1, R2, R3; T4: U4 => 4);
+suffix!(R0, R1, R2; T3: U3 => 3, T4: U4 => 4);
+suffix!(R0, R1; T2: U2 => 2, T3: U3 => 3, T4: U4 => 4);
+suffix!(R0; T1: U1 => 1, T2: U2 => 2, T3: U3 => 3, T4: U4 => 4);
+suffix!(; T0: U0 => 0, T1: U1 => 1, T2: U2 => 2, T3: U3 => 3, T4: U4 => 4);
+suffix!(R0, R1, R2, R3, R4; T5: U5 => 5);
+suffix!(R0, R1, R2, R3; T4: U4 => 4, T5: U5 => 5);
+suffix!(R0, R1, R2; T3: U3 => 3, T4: U4 => 4, T5: U5 => 5);
+suffix!(R0, R1; T2: U2 => 2, T3: U3 => 3, T4: U4 => 4, T5: U5 => 5);
+suffix!(R0; T1: U1 => 1, T2: U2 => 2, T3: U3 => 3, T4: U4 => 4, T5: U5 => 5);
+suffix!(; T0: U0 => 0, T1: U1 => 1, T2: U2 => 2, T3: U3 => 3, T4: U4 => 4, T5: U5 => 5);
+
+vec_prefix!(1; U0 => 0);
+vec_prefix!(2; U0 => 0, U1 => 1);
+vec_prefix!(3; U0 => 0, U1 => 1, U2 => 2);
+vec_prefix!(4; U0 => 0, U1 => 1, U2 => 2, U3 => 3);
+vec_prefix!(5; U0 => 0, U1 => 1, U2 => 2, U3 => 3, U4 => 4);
+vec_prefix!(6; U0 => 0, U1 => 1, U2 => 2, U3 => 3, U4 => 4, U5 => 5);
+
+vec_suffix!(1; U0 => 0);
+vec_suffix!(2; U0 => 0, U1 => 1);
+vec_suffix!(3; U0 => 0, U1 => 1, U2 => 2);
+vec_suffix!(4; U0 => 0, U1 => 1, U2 => 2, U3 => 3);
+vec_suffix!(5; U0 => 0, U1 => 1, U2 => 2, U3 => 3, U4 => 4);
+vec_suffix!(6; U0 => 0, U1 => 1, U2 => 2, U3 => 3, U4 => 4, U5 => 5);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Match;
+
+    #[test]
+    fn test_tuple_prefix_suffix() {
+        let args = (1u8, 2u16, "three", 4u32);
+        assert!(args.matches::<Prefix<(u64, u64)>>());
+        assert!(args.matches::<Suffix<(String, u64)>>());
+        assert_eq!(
+            Suffix::<(String, u64)>::opt_cast_from(args),
+            Some(Suffix(("three".to_string(), 4)))
+        );
+    }
+
+    #[test]
+    fn test_vec_prefix_suffix() {
+        let args = vec![1u8, 2, 3];
+        assert!(args.matches::<Prefix<(u16, u16, u16)>>());
+        assert!(!args.matches::<Prefix<(u16, u16, u16, u16)>>());
+        assert!(!args.matches::<Suffix<(u16, u16, u16, u16)>>());
+        assert_eq!(
+            Suffix::<(u16, u16)>::opt_cast_from(args).map(Suffix::into_inner),
+            Some((2, 3))
+        );
+    }
+
+    #[cfg(feature = "allocator_api")]
+    #[test]
+    fn test_vec_prefix_allocator() {
+        let mut args = Vec::new_in(std::alloc::System);
+        args.extend([1u8, 2, 3]);
+        assert_eq!(Prefix::<(u16,)>::opt_cast_from(args), Some(Prefix((1,))));
+    }
+}
@@ -0,0 +1,142 @@
+//! An [`Ffi`] wrapper to cast between Rust strings and C strings.
+
+use std::ffi::{CStr, CString};
+use std::ops::Deref;
+
+use super::TryCastFrom;
+
+/// A string checked for compatibility across an FFI boundary.
+///
+/// Casting from a Rust string into a [`CString`] fails if the string contains an interior NUL
+/// byte, and casting from a [`CStr`] or [`CString`] into a Rust string fails if it is not valid
+/// UTF-8. These casts would overlap with the blanket implementation of [`TryCastFrom`] for types
+/// which implement [`From`], so this explicit wrapper is used instead. The infallible
+/// directions, like `CString::from(&CStr)`, are already covered by the blanket implementation.
+/// Example:
+/// ```
+/// use std::ffi::CString;
+/// use safecast::{Ffi, TryCastFrom};
+///
+/// assert!(Ffi::<CString>::can_cast_from(&"hello"));
+/// assert!(Ffi::<CString>::opt_cast_from("hel\0lo".to_string()).is_none());
+///
+/// let c_str = c"hello";
+/// let hello = Ffi::<&str>::opt_cast_from(c_str).unwrap();
+/// assert_eq!(hello.into_inner(), "hello");
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Ffi<T>(pub T);
+
+impl<T> Ffi<T> {
+    /// Return the checked value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Ffi<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl From<Ffi<CString>> for CString {
+    fn from(c_string: Ffi<CString>) -> Self {
+        c_string.0
+    }
+}
+
+impl From<Ffi<String>> for String {
+    fn from(string: Ffi<String>) -> Self {
+        string.0
+    }
+}
+
+impl<'a> From<Ffi<&'a str>> for &'a str {
+    fn from(string: Ffi<&'a str>) -> Self {
+        string.0
+    }
+}
+
+impl<'a> TryCastFrom<&'a str> for Ffi<CString> {
+    fn can_cast_from(value: &&'a str) -> bool {
+        !value.as_bytes().contains(&0)
+    }
+
+    fn opt_cast_from(value: &'a str) -> Option<Self> {
+        CString::new(value).ok().map(Self)
+    }
+}
+
+impl TryCastFrom<String> for Ffi<CString> {
+    fn can_cast_from(value: &String) -> bool {
+        !value.as_bytes().contains(&0)
+    }
+
+    fn opt_cast_from(value: String) -> Option<Self> {
+        CString::new(value).ok().map(Self)
+    }
+}
+
+impl<'a> TryCastFrom<&'a CStr> for Ffi<&'a str> {
+    fn can_cast_from(value: &&'a CStr) -> bool {
+        value.to_str().is_ok()
+    }
+
+    fn opt_cast_from(value: &'a CStr) -> Option<Self> {
+        value.to_str().ok().map(Self)
+    }
+}
+
+impl<'a> TryCastFrom<&'a CStr> for Ffi<String> {
+    fn can_cast_from(value: &&'a CStr) -> bool {
+        value.to_str().is_ok()
+    }
+
+    fn opt_cast_from(value: &'a CStr) -> Option<Self> {
+        value.to_str().ok().map(|s| Self(s.to_string()))
+    }
+}
+
+impl TryCastFrom<CString> for Ffi<String> {
+    fn can_cast_from(value: &CString) -> bool {
+        value.to_str().is_ok()
+    }
+
+    fn opt_cast_from(value: CString) -> Option<Self> {
+        value.into_string().ok().map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CastInto, Match};
+
+    #[test]
+    fn test_c_string() {
+        assert!(Ffi::<CString>::can_cast_from(&"hello"));
+        assert!(!"hel\0lo".to_string().matches::<Ffi<CString>>());
+
+        let c_string: CString = Ffi::<CString>::opt_cast_from("hello").unwrap().cast_into();
+        assert_eq!(c_string.as_bytes(), b"hello");
+    }
+
+    #[test]
+    fn test_c_str() {
+        let valid = c"hello";
+        let invalid = c"\xff";
+
+        assert!(valid.matches::<Ffi<&str>>());
+        assert!(!invalid.matches::<Ffi<String>>());
+
+        let string: String = Ffi::<String>::opt_cast_from(valid.to_owned())
+            .unwrap()
+            .cast_into();
+
+        assert_eq!(string, "hello");
+        assert!(Ffi::<String>::opt_cast_from(invalid.to_owned()).is_none());
+    }
+}
@@ -0,0 +1,208 @@
+//! Errors which describe every field of a struct which could not be cast.
+
+use std::any::type_name;
+use std::convert::Infallible;
+use std::fmt;
+
+use super::TryCastFrom;
+
+/// A field of a struct which could not be cast into its target type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldError {
+    field: String,
+    expected: &'static str,
+    found: String,
+}
+
+impl FieldError {
+    /// Construct a new error for the named `field`, which could not be cast into `T`
+    /// from the value described by `found`.
+    pub fn new<T: ?Sized>(field: impl Into<String>, found: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            expected: type_name::<T>(),
+            found: found.into(),
+        }
+    }
+
+    /// The name (or nested path) of the field which could not be cast.
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// The name of the type which the field could not be cast into.
+    pub fn expected(&self) -> &'static str {
+        self.expected
+    }
+
+    /// A description of the value which could not be cast.
+    pub fn found(&self) -> &str {
+        &self.found
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: expected {}, found {}",
+            self.field, self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// Every field of a struct which could not be cast, in field order.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FieldErrors {
+    errors: Vec<FieldError>,
+}
+
+impl FieldErrors {
+    /// Construct a new, empty list of field errors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an error to this list.
+    pub fn push(&mut self, error: FieldError) {
+        self.errors.push(error)
+    }
+
+    /// Add the errors of the nested struct in the field `field` to this list.
+    pub fn extend_nested(&mut self, field: &str, nested: FieldErrors) {
+        self.errors
+            .extend(nested.errors.into_iter().map(|mut error| {
+                error.field = format!("{}.{}", field, error.field);
+                error
+            }))
+    }
+
+    /// Returns `true` if this list contains no errors.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The number of errors in this list.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Iterate over the errors in this list.
+    pub fn iter(&self) -> std::slice::Iter<'_, FieldError> {
+        self.errors.iter()
+    }
+}
+
+impl IntoIterator for FieldErrors {
+    type Item = FieldError;
+    type IntoIter = std::vec::IntoIter<FieldError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+impl fmt::Display for FieldErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cannot cast {} field(s)", self.errors.len())?;
+
+        for (i, error) in self.errors.iter().enumerate() {
+            let separator = if i == 0 { ": " } else { "; " };
+            write!(f, "{}{}", separator, error)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for FieldErrors {}
+
+impl From<Infallible> for FieldErrors {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+/// Trait for a fallible cast from a struct which reports every field which could not be cast,
+/// rather than stopping at the first. This can be derived with
+/// `#[cast(try_from = "Other", aggregate_errors)]` using the `derive` feature.
+///
+/// Example:
+/// ```
+/// use safecast::{FieldError, FieldErrors, TryCastFrom, TryCastFromFields};
+///
+/// struct Form {
+///     name: String,
+///     age: i64,
+/// }
+///
+/// #[derive(Debug)]
+/// struct Person {
+///     name: String,
+///     age: u8,
+/// }
+///
+/// impl TryCastFrom<Form> for Person {
+///     fn can_cast_from(form: &Form) -> bool {
+///         !form.name.is_empty() && u8::try_from(form.age).is_ok()
+///     }
+///
+///     fn opt_cast_from(form: Form) -> Option<Self> {
+///         let age = u8::try_from(form.age).ok()?;
+///         Some(Self { name: form.name, age }).filter(|person| !person.name.is_empty())
+///     }
+/// }
+///
+/// impl TryCastFromFields<Form> for Person {
+///     fn try_cast_from_fields(form: Form) -> Result<Self, FieldErrors> {
+///         let mut errors = FieldErrors::new();
+///
+///         if form.name.is_empty() {
+///             errors.push(FieldError::new::<String>("name", "\"\""));
+///         }
+///
+///         if u8::try_from(form.age).is_err() {
+///             errors.push(FieldError::new::<u8>("age", form.age.to_string()));
+///         }
+///
+///         if errors.is_empty() {
+///             Ok(Self::opt_cast_from(form).unwrap())
+///         } else {
+///             Err(errors)
+///         }
+///     }
+/// }
+///
+/// let errors = Person::try_cast_from_fields(Form { name: String::new(), age: -1 }).unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// ```
+pub trait TryCastFromFields<T>: TryCastFrom<T> {
+    /// Returns `Ok(Self)` if the source value can be cast into `Self`, otherwise an error for
+    /// each field which could not be cast.
+    fn try_cast_from_fields(value: T) -> Result<Self, FieldErrors>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_errors() {
+        let mut nested = FieldErrors::new();
+        nested.push(FieldError::new::<u8>("port", "70000"));
+
+        let mut errors = FieldErrors::new();
+        errors.push(FieldError::new::<String>("name", "None"));
+        errors.extend_nested("server", nested);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors.iter().nth(1).unwrap().field(), "server.port");
+        assert_eq!(
+            errors.to_string(),
+            "cannot cast 2 field(s): name: expected alloc::string::String, found None; \
+            server.port: expected u8, found 70000"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! A [`Flags`] wrapper to cast a set of fieldless enum variants to and from an integer bitmask.
+
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::ops::Deref;
+
+use super::{CastFrom, TryCastFrom};
+
+/// A fieldless enum each of whose variants is a distinct power of two.
+///
+/// This can be derived with `#[derive(Flag)]`, given the `derive` feature and a `#[repr]`
+/// attribute naming an unsigned integer type; the derive checks at compile time that each
+/// discriminant is a power of two.
+pub trait Flag: Copy + Eq + Hash + 'static {
+    /// The integer type of a bitmask of these flags.
+    type Bits;
+
+    /// Every variant of this enum.
+    const FLAGS: &'static [Self];
+
+    /// The single bit which represents this flag.
+    fn bit(self) -> Self::Bits;
+}
+
+/// A set of [`Flag`]s which can be cast into its bitmask, or cast from a bitmask with no unknown
+/// bits set.
+///
+/// Neither `HashSet<F>` nor an integer type is local to the crate which defines `F`, so the
+/// casts can't be implemented on `HashSet<F>` directly; this wrapper is used instead.
+/// Example:
+/// ```
+/// use std::collections::HashSet;
+/// use safecast::{CastFrom, Flag, Flags, TryCastFrom};
+///
+/// #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+/// enum Permission {
+///     Read = 1,
+///     Write = 2,
+/// }
+///
+/// impl Flag for Permission {
+///     type Bits = u32;
+///
+///     const FLAGS: &'static [Self] = &[Self::Read, Self::Write];
+///
+///     fn bit(self) -> u32 {
+///         self as u32
+///     }
+/// }
+///
+/// let flags = Flags::from(HashSet::from([Permission::Read, Permission::Write]));
+/// assert_eq!(u32::cast_from(flags), 3);
+///
+/// let flags = Flags::<Permission>::opt_cast_from(2u32).unwrap();
+/// assert_eq!(flags.into_inner(), HashSet::from([Permission::Write]));
+/// assert!(!Flags::<Permission>::can_cast_from(&4u32));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Flags<F: Flag>(pub HashSet<F>);
+
+impl<F: Flag> Flags<F> {
+    /// Return the set of flags.
+    pub fn into_inner(self) -> HashSet<F> {
+        self.0
+    }
+}
+
+impl<F: Flag> Default for Flags<F> {
+    fn default() -> Self {
+        Self(HashSet::new())
+    }
+}
+
+impl<F: Flag> Deref for Flags<F> {
+    type Target = HashSet<F>;
+
+    fn deref(&self) -> &HashSet<F> {
+        &self.0
+    }
+}
+
+impl<F: Flag> From<HashSet<F>> for Flags<F> {
+    fn from(flags: HashSet<F>) -> Self {
+        Self(flags)
+    }
+}
+
+impl<F: Flag> FromIterator<F> for Flags<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+macro_rules! flags {
+    ($($bits:ty),*) => {
+        $(
+            impl<F: Flag<Bits = $bits>> CastFrom<Flags<F>> for $bits {
+                fn cast_from(flags: Flags<F>) -> Self {
+                    flags.iter().fold(0, |bits, flag| bits | flag.bit())
+                }
+            }
+
+            impl<F: Flag<Bits = $bits>> TryCastFrom<$bits> for Flags<F> {
+                fn can_cast_from(bits: &$bits) -> bool {
+                    let known = F::FLAGS.iter().fold(0, |known, flag| known | flag.bit());
+                    bits & !known == 0
+                }
+
+                fn opt_cast_from(bits: $bits) -> Option<Self> {
+                    if Self::can_cast_from(&bits) {
+                        let flags = F::FLAGS.iter().copied().filter(|flag| bits & flag.bit() != 0);
+                        Some(flags.collect())
+                    } else {
+                        None
+                    }
+                }
+            }
+        )*
+    };
+}
+
+flags!(u8, u16, u32, u64, u128, usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Match;
+
+    #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+    enum Mode {
+        Read = 1,
+        Write = 2,
+        Append = 8,
+    }
+
+    impl Flag for Mode {
+        type Bits = u8;
+
+        const FLAGS: &'static [Self] = &[Self::Read, Self::Write, Self::Append];
+
+        fn bit(self) -> u8 {
+            self as u8
+        }
+    }
+
+    #[test]
+    fn test_flags() {
+        let flags: Flags<Mode> = [Mode::Read, Mode::Append].into_iter().collect();
+        assert_eq!(u8::cast_from(flags), 9);
+        assert_eq!(u8::cast_from(Flags::<Mode>::default()), 0);
+
+        let flags = Flags::<Mode>::opt_cast_from(11u8).unwrap();
+        assert_eq!(flags.len(), 3);
+        assert!(flags.contains(&Mode::Write));
+
+        assert!(!4u8.matches::<Flags<Mode>>());
+        assert!(Flags::<Mode>::opt_cast_from(17u8).is_none());
+    }
+}
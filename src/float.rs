@@ -0,0 +1,212 @@
+//! Wrappers which choose how a cast from a floating-point number treats NaN and infinity.
+
+use std::cmp::Ordering;
+use std::ops::Deref;
+
+use super::{CastFrom, TryCastFrom};
+
+/// A floating-point number which is neither NaN nor infinite.
+///
+/// Casting from a float into `Finite` rejects NaN and ±infinity, while casting into a plain
+/// `f32` or `f64` passes them through. Since a `Finite` can't be NaN, it implements [`Eq`] and
+/// [`Ord`]. Example:
+/// ```
+/// use safecast::{Finite, Match, TryCastFrom};
+///
+/// let finite = Finite::<f64>::opt_cast_from(1.5f32).unwrap();
+/// assert_eq!(*finite, 1.5);
+///
+/// assert!(!f64::NAN.matches::<Finite<f64>>());
+/// assert!(!f64::INFINITY.matches::<Finite<f64>>());
+///
+/// let mut values = vec![Finite::<f64>::opt_cast_from(2.).unwrap(), finite];
+/// values.sort();
+/// assert_eq!(values[0], finite);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Finite<T>(T);
+
+/// A floating-point number which is not NaN, but may be infinite.
+///
+/// Casting from a float into `NotNan` rejects NaN but accepts ±infinity. Since a `NotNan` can't
+/// be NaN, it implements [`Eq`] and [`Ord`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NotNan<T>(T);
+
+/// A floating-point number which is `None` if it was cast from NaN.
+///
+/// Casting from a float into `NanAsNone` always succeeds; ±infinity are kept. Example:
+/// ```
+/// use safecast::{CastFrom, NanAsNone, NonFiniteAsNone};
+///
+/// assert_eq!(NanAsNone::<f64>::cast_from(f64::NAN).0, None);
+/// assert_eq!(NanAsNone::<f64>::cast_from(f64::INFINITY).0, Some(f64::INFINITY));
+/// assert_eq!(NonFiniteAsNone::<f64>::cast_from(f32::NEG_INFINITY).0, None);
+/// assert_eq!(NonFiniteAsNone::<f32>::cast_from(0.5f32).0, Some(0.5));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct NanAsNone<T>(pub Option<T>);
+
+/// A floating-point number which is `None` if it was cast from NaN or ±infinity.
+///
+/// Casting from a float into `NonFiniteAsNone` always succeeds. See [`NanAsNone`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct NonFiniteAsNone<T>(pub Option<T>);
+
+macro_rules! checked {
+    ($wrapper:ident, $check:ident) => {
+        impl<T> $wrapper<T> {
+            /// Return the number.
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        impl<T> AsRef<T> for $wrapper<T> {
+            fn as_ref(&self) -> &T {
+                &self.0
+            }
+        }
+
+        impl<T> Deref for $wrapper<T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                &self.0
+            }
+        }
+
+        checked!(@cast $wrapper, $check; f32 => f32, f64);
+        checked!(@cast $wrapper, $check; f64 => f64);
+    };
+    (@cast $wrapper:ident, $check:ident; $f:ty => $($t:ty),+) => {
+        $(
+            impl TryCastFrom<$f> for $wrapper<$t> {
+                fn can_cast_from(value: &$f) -> bool {
+                    $check(*value)
+                }
+
+                fn opt_cast_from(value: $f) -> Option<Self> {
+                    if $check(value) {
+                        Some(Self(<$t>::from(value)))
+                    } else {
+                        None
+                    }
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! optional {
+    ($wrapper:ident, $check:ident) => {
+        impl<T> $wrapper<T> {
+            /// Return the number, if any.
+            pub fn into_inner(self) -> Option<T> {
+                self.0
+            }
+        }
+
+        impl<T> Deref for $wrapper<T> {
+            type Target = Option<T>;
+
+            fn deref(&self) -> &Option<T> {
+                &self.0
+            }
+        }
+
+        impl<T> From<$wrapper<T>> for Option<T> {
+            fn from(number: $wrapper<T>) -> Self {
+                number.0
+            }
+        }
+
+        optional!(@cast $wrapper, $check; f32 => f32, f64);
+        optional!(@cast $wrapper, $check; f64 => f64);
+    };
+    (@cast $wrapper:ident, $check:ident; $f:ty => $($t:ty),+) => {
+        $(
+            impl CastFrom<$f> for $wrapper<$t> {
+                fn cast_from(value: $f) -> Self {
+                    Self(Some(value).filter(|value| $check(*value)).map(<$t>::from))
+                }
+            }
+        )+
+    };
+}
+
+fn is_finite<F: Into<f64>>(value: F) -> bool {
+    value.into().is_finite()
+}
+
+fn is_not_nan<F: Into<f64>>(value: F) -> bool {
+    !value.into().is_nan()
+}
+
+checked!(Finite, is_finite);
+checked!(NotNan, is_not_nan);
+optional!(NanAsNone, is_not_nan);
+optional!(NonFiniteAsNone, is_finite);
+
+macro_rules! not_nan {
+    ($wrapper:ident; $($t:ty),*) => {
+        $(
+            impl From<$wrapper<$t>> for $t {
+                fn from(number: $wrapper<$t>) -> Self {
+                    number.0
+                }
+            }
+
+            impl Eq for $wrapper<$t> {}
+
+            impl PartialOrd for $wrapper<$t> {
+                fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Ord for $wrapper<$t> {
+                fn cmp(&self, other: &Self) -> Ordering {
+                    self.0.partial_cmp(&other.0).expect("not NaN")
+                }
+            }
+        )*
+    };
+}
+
+not_nan!(Finite; f32, f64);
+not_nan!(NotNan; f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Match;
+
+    #[test]
+    fn test_finite() {
+        assert!(1f32.matches::<Finite<f32>>());
+        assert!(!f32::NAN.matches::<Finite<f64>>());
+        assert!(Finite::<f64>::opt_cast_from(f64::NEG_INFINITY).is_none());
+        assert_eq!(
+            f64::cast_from(Finite::<f64>::opt_cast_from(2.).unwrap()),
+            2.
+        );
+
+        assert!(f64::INFINITY.matches::<NotNan<f64>>());
+        assert!(!f64::NAN.matches::<NotNan<f64>>());
+
+        let low = NotNan::<f64>::opt_cast_from(f64::NEG_INFINITY).unwrap();
+        let high = NotNan::<f64>::opt_cast_from(0.).unwrap();
+        assert_eq!(low.max(high), high);
+    }
+
+    #[test]
+    fn test_as_none() {
+        assert_eq!(NanAsNone::<f32>::cast_from(f32::NAN).into_inner(), None);
+        assert_eq!(*NanAsNone::<f64>::cast_from(1f32), Some(1.));
+        assert_eq!(
+            Option::from(NonFiniteAsNone::<f64>::cast_from(f64::INFINITY)),
+            None::<f64>
+        );
+    }
+}
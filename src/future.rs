@@ -0,0 +1,219 @@
+//! Asynchronous casting, for casts which need to await some external resource.
+
+use std::future::{ready, Future};
+
+use super::{expect_cast, TryCastFrom};
+
+/// Trait for defining an asynchronous cast operation when the source type cannot always be cast
+/// to the destination type, for example when a reference must be resolved from storage in order
+/// to cast it into its concrete payload. The contract is the same as [`TryCastFrom`]: when
+/// `can_cast_from_async` resolves to `true`, `opt_cast_from_async` *must* resolve to `Some(...)`,
+/// otherwise `try_cast_from_async` may panic.
+///
+/// The returned futures are not required to be [`Send`], so an implementation may hold a
+/// `!Send` value (like an `Rc`) across an `.await`. The future of a particular implementation
+/// is still `Send` whenever its state is, so it can be spawned on a multi-threaded executor.
+/// Every type which implements [`TryCastFrom`] implements `AsyncTryCastFrom` automatically.
+/// Example:
+/// ```
+/// use safecast::AsyncTryCastFrom;
+///
+/// struct Ref(usize);
+///
+/// #[derive(Debug, Eq, PartialEq)]
+/// struct Payload(&'static str);
+///
+/// async fn load(id: usize) -> Option<&'static str> {
+///     ["zero", "one"].get(id).copied()
+/// }
+///
+/// impl AsyncTryCastFrom<Ref> for Payload {
+///     async fn can_cast_from_async(value: &Ref) -> bool {
+///         load(value.0).await.is_some()
+///     }
+///
+///     async fn opt_cast_from_async(value: Ref) -> Option<Self> {
+///         load(value.0).await.map(Payload)
+///     }
+/// }
+///
+/// futures::executor::block_on(async {
+///     assert_eq!(Payload::opt_cast_from_async(Ref(1)).await, Some(Payload("one")));
+///     assert!(!Payload::can_cast_from_async(&Ref(2)).await);
+///     assert_eq!(u64::opt_cast_from_async(1u8).await, Some(1));
+/// });
+/// ```
+pub trait AsyncTryCastFrom<T>: Sized {
+    /// Test if `value` can be cast into `Self`.
+    fn can_cast_from_async(value: &T) -> impl Future<Output = bool>;
+
+    /// Resolves to `Some(Self)` if the source value can be cast into `Self`, otherwise `None`.
+    fn opt_cast_from_async(value: T) -> impl Future<Output = Option<Self>>;
+
+    /// Resolves to `Ok(Self)` if the source value can be cast into `Self`, otherwise calls
+    /// `on_err`.
+    fn try_cast_from_async<Err, OnErr: FnOnce(&T) -> Err>(
+        value: T,
+        on_err: OnErr,
+    ) -> impl Future<Output = Result<Self, Err>> {
+        async move {
+            if Self::can_cast_from_async(&value).await {
+                #[cfg(feature = "metrics")]
+                crate::metrics::record::<T, Self>(true);
+
+                Ok(expect_cast::<T, Self>(
+                    Self::opt_cast_from_async(value).await,
+                ))
+            } else {
+                #[cfg(feature = "metrics")]
+                crate::metrics::record::<T, Self>(false);
+
+                Err(on_err(&value))
+            }
+        }
+    }
+}
+
+/// Trait for defining an asynchronous cast operation when the destination type cannot always be
+/// cast from the source type. The inverse of [`AsyncTryCastFrom`].
+/// Prefer implementing `AsyncTryCastFrom` over `AsyncTryCastInto` because implementing
+/// `AsyncTryCastFrom` automatically provides an implementation of `AsyncTryCastInto`.
+pub trait AsyncTryCastInto<T>: Sized {
+    /// Test if `self` can be cast into `T`.
+    fn can_cast_into_async(&self) -> impl Future<Output = bool>;
+
+    /// Resolves to `Some(T)` if `self` can be cast into `T`, otherwise `None`.
+    fn opt_cast_into_async(self) -> impl Future<Output = Option<T>>;
+
+    /// Resolves to `Ok(T)` if `self` can be cast into `T`, otherwise calls `on_err`.
+    fn try_cast_into_async<Err, OnErr: FnOnce(&Self) -> Err>(
+        self,
+        on_err: OnErr,
+    ) -> impl Future<Output = Result<T, Err>> {
+        async move {
+            if self.can_cast_into_async().await {
+                #[cfg(feature = "metrics")]
+                crate::metrics::record::<Self, T>(true);
+
+                Ok(expect_cast::<Self, T>(self.opt_cast_into_async().await))
+            } else {
+                #[cfg(feature = "metrics")]
+                crate::metrics::record::<Self, T>(false);
+
+                Err(on_err(&self))
+            }
+        }
+    }
+}
+
+impl<F, T: TryCastFrom<F>> AsyncTryCastFrom<F> for T {
+    fn can_cast_from_async(value: &F) -> impl Future<Output = bool> {
+        ready(T::can_cast_from(value))
+    }
+
+    fn opt_cast_from_async(value: F) -> impl Future<Output = Option<Self>> {
+        ready(T::opt_cast_from(value))
+    }
+}
+
+impl<F, T: AsyncTryCastFrom<F>> AsyncTryCastInto<T> for F {
+    fn can_cast_into_async(&self) -> impl Future<Output = bool> {
+        T::can_cast_from_async(self)
+    }
+
+    fn opt_cast_into_async(self) -> impl Future<Output = Option<T>> {
+        T::opt_cast_from_async(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::Parsed;
+
+    struct Ref(usize);
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Payload(u64);
+
+    async fn resolve(id: usize) -> Option<u64> {
+        [1, 2, 3].get(id).copied()
+    }
+
+    impl AsyncTryCastFrom<Ref> for Payload {
+        async fn can_cast_from_async(value: &Ref) -> bool {
+            resolve(value.0).await.is_some()
+        }
+
+        async fn opt_cast_from_async(value: Ref) -> Option<Self> {
+            resolve(value.0).await.map(Payload)
+        }
+    }
+
+    #[test]
+    fn test_async_try_cast() {
+        block_on(async {
+            assert!(Payload::can_cast_from_async(&Ref(0)).await);
+            assert_eq!(Payload::opt_cast_from_async(Ref(2)).await, Some(Payload(3)));
+            assert_eq!(Payload::try_cast_from_async(Ref(3), |r| r.0).await, Err(3));
+
+            let payload: Option<Payload> = Ref(1).opt_cast_into_async().await;
+            assert_eq!(payload, Some(Payload(2)));
+        });
+    }
+
+    fn assert_send<T: Send>(value: T) -> T {
+        value
+    }
+
+    #[test]
+    fn test_async_send() {
+        block_on(async {
+            assert!(assert_send(Payload::can_cast_from_async(&Ref(0))).await);
+            let payload = assert_send(Payload::try_cast_from_async(Ref(1), |r| r.0)).await;
+            assert_eq!(payload, Ok(Payload(2)));
+            assert_eq!(
+                assert_send(Ref(3).try_cast_into_async(|r| r.0)).await,
+                Err::<Payload, _>(3)
+            );
+        });
+    }
+
+    #[test]
+    fn test_async_not_send() {
+        use std::rc::Rc;
+
+        struct Shared(Rc<u64>);
+
+        impl AsyncTryCastFrom<Ref> for Shared {
+            async fn can_cast_from_async(value: &Ref) -> bool {
+                resolve(value.0).await.is_some()
+            }
+
+            async fn opt_cast_from_async(value: Ref) -> Option<Self> {
+                let cached = Rc::new(resolve(value.0).await?);
+                resolve(0).await?;
+                Some(Shared(cached))
+            }
+        }
+
+        block_on(async {
+            let shared = Shared::opt_cast_from_async(Ref(2)).await.unwrap();
+            assert_eq!(*shared.0, 3);
+        });
+    }
+
+    #[test]
+    fn test_async_bridge() {
+        block_on(async {
+            assert!(!Parsed::<u8>::can_cast_from_async(&"300").await);
+            assert_eq!(
+                Parsed::<u8>::opt_cast_from_async("30").await,
+                Some(Parsed(30))
+            );
+            assert_eq!(u64::try_cast_from_async(1u8, |_| ()).await, Ok(1));
+        });
+    }
+}
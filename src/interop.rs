@@ -0,0 +1,197 @@
+//! Macros to adapt between the traits in this crate and the standard conversion traits.
+
+/// Implement [`TryCastFrom`](crate::TryCastFrom) for a type which already implements
+/// [`TryFrom`].
+///
+/// Because [`TryFrom`] consumes its input, the generated `can_cast_from` method clones the
+/// source value and attempts the conversion, so the source type must implement [`Clone`].
+/// To avoid this cost, provide a closure which tests the source value instead.
+///
+/// This macro can't be replaced by a blanket implementation, since that would overlap with the
+/// blanket implementation of `TryCastFrom` for types which implement [`From`].
+/// Example:
+/// ```
+/// use safecast::{impl_cast_from_try_from, TryCastFrom};
+///
+/// #[derive(Clone)]
+/// struct Even(u32);
+///
+/// struct Half(u32);
+///
+/// impl TryFrom<Even> for Half {
+///     type Error = ();
+///
+///     fn try_from(even: Even) -> Result<Self, ()> {
+///         if even.0 % 2 == 0 {
+///             Ok(Self(even.0 / 2))
+///         } else {
+///             Err(())
+///         }
+///     }
+/// }
+///
+/// impl_cast_from_try_from!(Half, Even);
+/// assert!(Half::can_cast_from(&Even(2)));
+/// assert!(Half::opt_cast_from(Even(3)).is_none());
+/// ```
+///
+/// With a closure to test the source value without cloning it:
+/// ```
+/// # use safecast::{impl_cast_from_try_from, TryCastFrom};
+/// # struct Even(u32);
+/// # struct Half(u32);
+/// # impl TryFrom<Even> for Half {
+/// #     type Error = ();
+/// #     fn try_from(even: Even) -> Result<Self, ()> {
+/// #         if even.0 % 2 == 0 { Ok(Self(even.0 / 2)) } else { Err(()) }
+/// #     }
+/// # }
+/// impl_cast_from_try_from!(Half, Even, |even| even.0 % 2 == 0);
+/// assert!(Half::can_cast_from(&Even(2)));
+/// ```
+#[macro_export]
+macro_rules! impl_cast_from_try_from {
+    ($target:ty, $source:ty) => {
+        impl $crate::TryCastFrom<$source> for $target {
+            fn can_cast_from(value: &$source) -> bool {
+                <$target as ::std::convert::TryFrom<$source>>::try_from(value.clone()).is_ok()
+            }
+
+            fn opt_cast_from(value: $source) -> Option<Self> {
+                <$target as ::std::convert::TryFrom<$source>>::try_from(value).ok()
+            }
+        }
+    };
+    ($target:ty, $source:ty, $can_cast:expr) => {
+        impl $crate::TryCastFrom<$source> for $target {
+            fn can_cast_from(value: &$source) -> bool {
+                let can_cast: fn(&$source) -> bool = $can_cast;
+                can_cast(value)
+            }
+
+            fn opt_cast_from(value: $source) -> Option<Self> {
+                <$target as ::std::convert::TryFrom<$source>>::try_from(value).ok()
+            }
+        }
+    };
+}
+
+/// Implement [`TryFrom`] for a type which implements [`CastFrom`](crate::CastFrom) or
+/// [`TryCastFrom`](crate::TryCastFrom), with [`CastError`](crate::CastError) as the error type.
+/// This also provides an implementation of [`TryInto`] for the source type.
+///
+/// There is no equivalent macro to implement [`From`] for a type which implements `CastFrom`,
+/// because the blanket implementation of `CastFrom` for types which implement `From` would
+/// conflict with the existing implementation. Implement `From` instead of `CastFrom` in that
+/// case; `CastFrom` is then provided automatically.
+/// Example:
+/// ```
+/// use safecast::{impl_try_from_cast_from, CastError, TryCastFrom};
+///
+/// struct Even(u32);
+///
+/// impl TryCastFrom<u32> for Even {
+///     fn can_cast_from(value: &u32) -> bool {
+///         value % 2 == 0
+///     }
+///
+///     fn opt_cast_from(value: u32) -> Option<Self> {
+///         if value % 2 == 0 {
+///             Some(Self(value))
+///         } else {
+///             None
+///         }
+///     }
+/// }
+///
+/// impl_try_from_cast_from!(Even, u32);
+///
+/// assert!(Even::try_from(2).is_ok());
+///
+/// let err: Result<Even, CastError> = 3u32.try_into();
+/// assert!(err.is_err());
+/// ```
+#[macro_export]
+macro_rules! impl_try_from_cast_from {
+    ($target:ty, $source:ty) => {
+        impl ::std::convert::TryFrom<$source> for $target {
+            type Error = $crate::CastError;
+
+            fn try_from(value: $source) -> Result<Self, Self::Error> {
+                <$target as $crate::TryCastFrom<$source>>::try_cast_from(value, |_| {
+                    $crate::CastError::new::<$source, $target>()
+                })
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CastError, CastFrom, Match, TryCastFrom};
+
+    #[derive(Clone)]
+    struct Digits(String);
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Number(u64);
+
+    impl TryFrom<Digits> for Number {
+        type Error = std::num::ParseIntError;
+
+        fn try_from(digits: Digits) -> Result<Self, Self::Error> {
+            digits.0.parse().map(Self)
+        }
+    }
+
+    impl_cast_from_try_from!(Number, Digits);
+
+    #[test]
+    fn test_cast_from_try_from() {
+        let digits = Digits("123".to_string());
+        assert!(digits.matches::<Number>());
+        assert_eq!(Number::opt_cast_from(digits), Some(Number(123)));
+        assert!(!Digits("abc".to_string()).matches::<Number>());
+    }
+
+    struct Celsius(f64);
+
+    struct Fahrenheit(f64);
+
+    impl CastFrom<Celsius> for Fahrenheit {
+        fn cast_from(celsius: Celsius) -> Self {
+            Self(celsius.0 * 1.8 + 32.)
+        }
+    }
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Odd(u64);
+
+    impl TryCastFrom<Number> for Odd {
+        fn can_cast_from(number: &Number) -> bool {
+            number.0 % 2 == 1
+        }
+
+        fn opt_cast_from(number: Number) -> Option<Self> {
+            if number.0 % 2 == 1 {
+                Some(Self(number.0))
+            } else {
+                None
+            }
+        }
+    }
+
+    impl_try_from_cast_from!(Odd, Number);
+    impl_try_from_cast_from!(Fahrenheit, Celsius);
+
+    #[test]
+    fn test_try_from_cast_from() {
+        assert_eq!(Odd::try_from(Number(1)), Ok(Odd(1)));
+        assert_eq!(
+            Odd::try_from(Number(2)),
+            Err(CastError::new::<Number, Odd>())
+        );
+
+        assert_eq!(Fahrenheit::try_from(Celsius(100.)).unwrap().0, 212.);
+    }
+}
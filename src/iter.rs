@@ -0,0 +1,259 @@
+//! Extension traits to cast the items of an [`Iterator`].
+
+use std::iter::Map;
+
+use super::{
+    expect_cast, CastBudget, CastError, CastFrom, CastLimits, FromPairs, InsertPairs, KeyConflict,
+    KeyConflictError, MapCastError, TryCastFrom, TryCastFromBounded,
+};
+
+/// An iterator which casts each item of `I` into `T`, returned by [`CastIter::cast_each`].
+pub type CastEach<I, T> = Map<I, fn(<I as Iterator>::Item) -> T>;
+
+/// An iterator which attempts to cast each item of `I` into `T`, returned by
+/// [`CastIter::try_cast_each`].
+pub type TryCastEach<I, T> = Map<I, fn(<I as Iterator>::Item) -> Option<T>>;
+
+/// Blanket implementation of convenience methods to cast each item of an [`Iterator`].
+/// Do not implement this trait.
+///
+/// Example:
+/// ```
+/// use safecast::CastIter;
+///
+/// let wide: Vec<u64> = [1u8, 2, 3].into_iter().cast_each().collect();
+/// assert_eq!(wide, [1, 2, 3]);
+///
+/// let strs = ["a", "b"].into_iter().cast_each::<String>();
+/// assert_eq!(strs.collect::<Vec<_>>(), ["a", "b"]);
+/// ```
+pub trait CastIter: Iterator + Sized {
+    /// Cast each item of this iterator into `T`.
+    ///
+    /// To cast a slice of numbers in bulk, collect `slice.iter().copied().cast_each()`; the
+    /// compiler auto-vectorizes the loop, so there is no separate SIMD cast.
+    fn cast_each<T: CastFrom<Self::Item>>(self) -> CastEach<Self, T> {
+        self.map(T::cast_from)
+    }
+
+    /// Attempt to cast each item of this iterator into `T`, yielding `None` for an item which
+    /// cannot be cast. Collect the result into an `Option` to stop at the first failure.
+    fn try_cast_each<T: TryCastFrom<Self::Item>>(self) -> TryCastEach<Self, T> {
+        self.map(T::opt_cast_from)
+    }
+}
+
+impl<I: Iterator> CastIter for I {}
+
+/// Blanket implementation of convenience methods to cast an [`Iterator`] of key-value pairs.
+/// Do not implement this trait.
+pub trait CastPairs<K, V>: Iterator<Item = (K, V)> + Sized {
+    /// Cast each key and value of this iterator and collect them into the map `M`.
+    /// Stops at the first entry which cannot be cast, returning its (uncast) key in the error.
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    /// use safecast::CastPairs;
+    ///
+    /// let entries = vec![("a", 1u8), ("b", 2u8)];
+    /// let map = entries.into_iter().try_cast_map::<HashMap<String, u64>>().unwrap();
+    /// assert_eq!(map["a"], 1);
+    /// ```
+    fn try_cast_map<M>(self) -> Result<M, MapCastError<K>>
+    where
+        M: FromPairs,
+        M::Key: TryCastFrom<K>,
+        M::Value: TryCastFrom<V>,
+    {
+        self.map(|(key, value)| cast_pair(key, value)).collect()
+    }
+
+    /// Cast each key and value of this iterator and insert them into the map `M`, resolving
+    /// entries whose keys are cast into the same key according to `policy`.
+    /// Stops at the first entry which cannot be cast, returning its (uncast) key in the error.
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    /// use safecast::{CastPairs, KeyConflict, KeyConflictError};
+    ///
+    /// let counts = HashMap::from([("a", 1u8), ("A", 2u8)]);
+    /// let lower = || counts.iter().map(|(key, n)| (key.to_lowercase(), *n));
+    ///
+    /// let merged = lower()
+    ///     .try_cast_map_with::<HashMap<String, u64>>(KeyConflict::merge(|a, b| a + b))
+    ///     .unwrap();
+    /// assert_eq!(merged["a"], 3);
+    ///
+    /// let conflict = lower().try_cast_map_with::<HashMap<String, u64>>(KeyConflict::Error);
+    /// assert_eq!(conflict, Err(KeyConflictError::Conflict("a".to_string())));
+    /// ```
+    fn try_cast_map_with<M>(
+        self,
+        mut policy: KeyConflict<M::Value>,
+    ) -> Result<M, KeyConflictError<K, M::Key>>
+    where
+        M: InsertPairs,
+        M::Key: TryCastFrom<K>,
+        M::Value: TryCastFrom<V>,
+    {
+        let mut map = M::default();
+
+        for (key, value) in self {
+            let (key, value) = cast_pair(key, value)?;
+
+            policy
+                .insert(&mut map, key, value)
+                .map_err(KeyConflictError::Conflict)?;
+        }
+
+        Ok(map)
+    }
+}
+
+impl<K, V, I: Iterator<Item = (K, V)>> CastPairs<K, V> for I {}
+
+/// Cast a key and value, or describe the first of them which can't be cast.
+fn cast_pair<K, V, KT, VT>(key: K, value: V) -> Result<(KT, VT), MapCastError<K>>
+where
+    KT: TryCastFrom<K>,
+    VT: TryCastFrom<V>,
+{
+    if !KT::can_cast_from(&key) {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record::<K, KT>(false);
+
+        Err(MapCastError::new(key, CastError::new::<K, KT>()))
+    } else if !VT::can_cast_from(&value) {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record::<V, VT>(false);
+
+        Err(MapCastError::new(key, CastError::new::<V, VT>()))
+    } else {
+        #[cfg(feature = "metrics")]
+        {
+            crate::metrics::record::<K, KT>(true);
+            crate::metrics::record::<V, VT>(true);
+        }
+
+        let key = expect_cast::<K, KT>(KT::opt_cast_from(key));
+        let value = expect_cast::<V, VT>(VT::opt_cast_from(value));
+        Ok((key, value))
+    }
+}
+
+/// Blanket implementation of convenience methods to check whether the borrowed items of an
+/// [`Iterator`] can be cast into a target type, without moving or casting them. Each method stops
+/// at the first item which decides the result. Do not implement this trait.
+///
+/// Example:
+/// ```
+/// use safecast::{MatchEach, Parsed};
+///
+/// let items = ["1", "2", "x", "4"];
+/// assert!(!items.iter().matches_all::<Parsed<u8>>());
+/// assert_eq!(items.iter().position_mismatch::<Parsed<u8>>(), Some(2));
+/// assert!(items[..2].iter().matches_all::<Parsed<u8>>());
+/// assert!(items[2..3].iter().matches_none::<Parsed<u8>>());
+/// ```
+pub trait MatchEach<'a, E: 'a>: Iterator<Item = &'a E> + Sized {
+    /// Returns `true` if every item of this iterator can be cast into `T`.
+    fn matches_all<T: TryCastFrom<E>>(mut self) -> bool {
+        self.all(T::can_cast_from)
+    }
+
+    /// Returns `true` if no item of this iterator can be cast into `T`.
+    fn matches_none<T: TryCastFrom<E>>(mut self) -> bool {
+        !self.any(T::can_cast_from)
+    }
+
+    /// Returns the index of the first item of this iterator which cannot be cast into `T`.
+    fn position_mismatch<T: TryCastFrom<E>>(mut self) -> Option<usize> {
+        self.position(|item| !T::can_cast_from(item))
+    }
+
+    /// Returns `true` if every item of this iterator can be cast into `T` within the given
+    /// `limits`, shared by all the items as though they were the elements of one sequence.
+    fn matches_all_bounded<T: TryCastFromBounded<E>>(self, limits: CastLimits) -> bool {
+        let mut budget = CastBudget::new(limits);
+        budget.all::<E, T, _>(self)
+    }
+}
+
+impl<'a, E: 'a, I: Iterator<Item = &'a E>> MatchEach<'a, E> for I {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{Each, Predicate, Validated};
+
+    #[test]
+    fn test_try_cast_each() {
+        let items = vec![1u64, 20, 3];
+        let cast: Option<Vec<Validated<u64, Small>>> = items.into_iter().try_cast_each().collect();
+        assert_eq!(cast, None);
+
+        let cast: Vec<Option<Validated<u64, Small>>> = [1, 2].into_iter().try_cast_each().collect();
+        assert!(cast.iter().all(Option::is_some));
+    }
+
+    #[test]
+    fn test_match_each() {
+        let items = [1u64, 2, 30, 4];
+        assert!(!items.iter().matches_all::<Validated<u64, Small>>());
+        assert!(!items.iter().matches_none::<Validated<u64, Small>>());
+        assert_eq!(
+            items.iter().position_mismatch::<Validated<u64, Small>>(),
+            Some(2)
+        );
+
+        let empty: [u64; 0] = [];
+        assert!(empty.iter().matches_all::<Validated<u64, Small>>());
+        assert!(empty.iter().matches_none::<Validated<u64, Small>>());
+    }
+
+    #[test]
+    fn test_match_each_bounded() {
+        let rows = [vec![1u8, 2], vec![3, 4]];
+        assert!(rows
+            .iter()
+            .matches_all_bounded::<Each<Vec<u64>>>(CastLimits::new(2, 6)));
+        assert!(!rows
+            .iter()
+            .matches_all_bounded::<Each<Vec<u64>>>(CastLimits::new(2, 5)));
+        assert!(!rows
+            .iter()
+            .matches_all_bounded::<Each<Vec<u64>>>(CastLimits::new(1, 6)));
+    }
+
+    #[test]
+    fn test_try_cast_map() {
+        let valid = [("a", 1u8), ("b", 2u8)];
+        let map = valid
+            .into_iter()
+            .try_cast_map::<BTreeMap<String, u16>>()
+            .unwrap();
+
+        assert_eq!(map["b"], 2);
+
+        let invalid = [(1u64, 1u64), (2, u64::MAX), (3, 3)];
+        let err = invalid
+            .into_iter()
+            .try_cast_map::<BTreeMap<u64, Validated<u64, Small>>>()
+            .unwrap_err();
+
+        assert_eq!(*err.key(), 2);
+        assert_eq!(err.error().source_type(), "u64");
+    }
+
+    struct Small;
+
+    impl Predicate<u64> for Small {
+        fn test(value: &u64) -> bool {
+            *value < 10
+        }
+    }
+}
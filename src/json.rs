@@ -0,0 +1,164 @@
+//! Casting from JSON strings, using [`serde_json`].
+
+use serde_json::Value;
+
+use super::document::cast_parsed;
+use super::{Document, FormatCastError, ParseError, TryCastFrom};
+
+/// An error which occurs when a JSON string cannot be cast into a target type.
+pub type JsonCastError = FormatCastError<serde_json::Error>;
+
+impl ParseError for serde_json::Error {
+    const FORMAT: &'static str = "JSON";
+}
+
+impl Document for Value {
+    fn as_bool(&self) -> Option<bool> {
+        Value::as_bool(self)
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        Value::as_i64(self)
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        Value::as_u64(self)
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        Value::as_f64(self)
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        Value::as_str(self)
+    }
+
+    fn is_null(&self) -> bool {
+        Value::is_null(self)
+    }
+
+    fn as_seq(&self) -> Option<&[Self]> {
+        self.as_array().map(Vec::as_slice)
+    }
+
+    fn into_seq(self) -> Option<Vec<Self>> {
+        match self {
+            Self::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    fn as_map(&self) -> Option<Vec<(&str, &Self)>> {
+        self.as_object().map(|map| {
+            map.iter()
+                .map(|(key, value)| (key.as_str(), value))
+                .collect()
+        })
+    }
+
+    fn into_map(self) -> Option<Vec<(String, Self)>> {
+        match self {
+            Self::Object(map) => Some(map.into_iter().collect()),
+            _ => None,
+        }
+    }
+}
+
+crate::decoded!(Value);
+
+/// Blanket implementation of a convenience method `try_cast_from_json` which parses a JSON
+/// string and casts the resulting [`Value`] in one call. Do not implement this trait.
+///
+/// To parse a primitive, a string, a `Vec`, or a map, cast into a [`Decoded`](crate::Decoded)
+/// wrapper, e.g. `Decoded::<Vec<u64>>::try_cast_from_json("[1, 2]")`; to parse your own type,
+/// implement `TryCastFrom<Value>` for it. Example:
+/// ```
+/// use safecast::{JsonCastError, TryCastFrom, TryCastFromJson};
+/// use serde_json::Value;
+///
+/// #[derive(Debug)]
+/// struct Id(u64);
+///
+/// impl TryCastFrom<Value> for Id {
+///     fn can_cast_from(value: &Value) -> bool {
+///         value.is_u64()
+///     }
+///
+///     fn opt_cast_from(value: Value) -> Option<Self> {
+///         value.as_u64().map(Id)
+///     }
+/// }
+///
+/// assert_eq!(Id::try_cast_from_json("42").unwrap().0, 42);
+/// assert!(matches!(Id::try_cast_from_json("4 2"), Err(JsonCastError::Parse(_))));
+/// assert!(matches!(Id::try_cast_from_json("-42"), Err(JsonCastError::Cast(_))));
+///
+/// let ids = safecast::Decoded::<Vec<u64>>::try_cast_from_json("[1, 2]").unwrap();
+/// assert_eq!(ids.into_inner(), [1, 2]);
+/// ```
+pub trait TryCastFromJson: TryCastFrom<Value> {
+    /// Parse `json` and cast the resulting [`Value`] into `Self`.
+    fn try_cast_from_json(json: &str) -> Result<Self, JsonCastError> {
+        cast_parsed(serde_json::from_str(json))
+    }
+}
+
+impl<T: TryCastFrom<Value>> TryCastFromJson for T {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::Decoded;
+
+    #[test]
+    fn test_try_cast_from_json() {
+        let value = Value::try_cast_from_json(r#"{"a": [1, 2]}"#).unwrap();
+        assert_eq!(value["a"][1], 2);
+
+        let err = Value::try_cast_from_json("{").unwrap_err();
+        assert!(err.to_string().starts_with("invalid JSON"));
+    }
+
+    #[test]
+    fn test_decoded_json() {
+        assert_eq!(*Decoded::<u8>::try_cast_from_json("255").unwrap(), 255);
+        assert!(Decoded::<u8>::try_cast_from_json("256").is_err());
+        assert_eq!(*Decoded::<i64>::try_cast_from_json("-1").unwrap(), -1);
+        assert!(Decoded::<u64>::try_cast_from_json("-1").is_err());
+        assert_eq!(*Decoded::<f32>::try_cast_from_json("0.5").unwrap(), 0.5);
+        assert!(Decoded::<f32>::try_cast_from_json("0.1").is_err());
+        assert!(*Decoded::<bool>::try_cast_from_json("true").unwrap());
+
+        let exact = (1u64 << 53).to_string();
+        assert_eq!(
+            *Decoded::<f64>::try_cast_from_json(&exact).unwrap(),
+            2f64.powi(53)
+        );
+        assert_eq!(
+            *Decoded::<f32>::try_cast_from_json(&exact).unwrap(),
+            2f32.powi(53)
+        );
+        assert!(Decoded::<f64>::try_cast_from_json(&((1u64 << 53) + 1).to_string()).is_err());
+        assert!(Decoded::<f64>::try_cast_from_json(&u64::MAX.to_string()).is_err());
+        assert!(Decoded::<f32>::try_cast_from_json(&u64::MAX.to_string()).is_err());
+        assert!(Decoded::<f32>::try_cast_from_json("16777217").is_err());
+        assert_eq!(*Decoded::<f64>::try_cast_from_json("-3").unwrap(), -3.);
+        assert!(Decoded::<f64>::try_cast_from_json("-9007199254740993").is_err());
+
+        let name = Decoded::<String>::try_cast_from_json(r#""a""#).unwrap();
+        assert_eq!(name.into_inner(), "a");
+
+        let rows = Decoded::<Vec<Vec<u16>>>::try_cast_from_json("[[1], [2, 3]]").unwrap();
+        assert_eq!(rows.into_inner(), [vec![1], vec![2, 3]]);
+
+        let err = Decoded::<Vec<u16>>::try_cast_from_json(r#"[1, "2"]"#).unwrap_err();
+        assert!(matches!(err, JsonCastError::Cast(_)));
+
+        let json = r#"{"a": [1], "b": null}"#;
+        let map = Decoded::<HashMap<String, Option<Vec<u8>>>>::try_cast_from_json(json).unwrap();
+        assert_eq!(map["a"], Some(vec![1]));
+        assert_eq!(map["b"], None);
+    }
+}
@@ -1,10 +1,155 @@
 //! `safecast` defines traits analogous to [`From`], [`Into`], [`TryFrom`], and [`TryInto`] to
 //! standardize the implementation of casting between Rust types. The `can_cast_from` and
 //! `can_cast_into` methods borrow the source value, allowing pattern matching without moving.
+//!
+//! Every conversion provided by [`From`] is also a [`CastFrom`], so shared string types like
+//! `Arc<str>`, `Rc<str>`, and `Box<str>` can already be cast from a `String` or a `&str`, and a
+//! `Box<str>` can be cast back into a `String` without copying. The copying casts from
+//! `Arc<str>` or `Rc<str>` into `String` overlap with a `From` implementation which the standard
+//! library could add in the future, so they can't be provided; cast from the borrowed `&str`
+//! instead, e.g. `String::cast_from(&*shared)`.
+//!
+//! A blanket `CastFrom<Infallible>` (or `CastFrom<!>`) implementation for every type can't be
+//! provided, because it would overlap with the reflexive `From<T> for T` when `T` is itself
+//! uninhabited. Instead, the error types in this crate implement `From<Infallible>`, so generic
+//! code which casts an uninhabited error type into one of them satisfies a `CastFrom` bound.
+//!
+//! The `derive` feature provides a `Cast` derive macro to implement casts between types with
+//! matching structure, an `AsType` derive macro to implement [`AsType`] for the variants of an
+//! enum, a `Flag` derive macro to cast between fieldless enums and integer bitmasks, and a
+//! `VariantName` derive macro to describe a failed cast by enum variant.
+//!
+//! The `futures` feature provides the `AsyncTryCastFrom` and `AsyncTryCastInto` traits, for
+//! casts which must await an external resource. Despite its name, it does not depend on the
+//! `futures` crate: the traits only use `std::future`, so any executor can drive them. It is
+//! opt-in because returning `impl Future` from a trait method requires Rust 1.75 or later.
+//!
+//! The `metrics` feature counts the successes and failures of fallible casts by source and
+//! target type; see the [`metrics`] module.
+//!
+//! The `allocator_api` feature, which requires a nightly compiler, makes the casts from `Vec`
+//! generic over its allocator `A`. [`Each`] (into a `Vec` or boxed slice), [`Chunks`] and
+//! [`CastDrain`] allocate their result with a clone of `A`; [`Prefix`], [`Suffix`],
+//! [`NonEmpty`] and [`Validated`] accept a `Vec<T, A>` source. The other casts, including those
+//! from a `Box`, use the global allocator.
+
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+
+#[cfg(feature = "derive")]
+extern crate self as safecast;
 
 #[allow(unused_imports)]
 use std::convert::{TryFrom, TryInto};
 
+mod bounded;
+mod buffer;
+mod casted;
+mod chain;
+mod chunks;
+mod cow;
+#[cfg(feature = "decimal")]
+mod decimal;
+#[cfg(feature = "derive")]
+mod derive;
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+mod document;
+mod drain;
+mod each;
+#[cfg(feature = "either")]
+mod either;
+mod error;
+mod extract;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod field;
+mod flags;
+mod float;
+#[cfg(feature = "futures")]
+mod future;
+mod interop;
+mod iter;
+#[cfg(feature = "json")]
+mod json;
+mod map;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+mod nested;
+mod option;
+mod parse;
+mod pipeline;
+pub mod registry;
+mod result;
+mod targets;
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(feature = "url")]
+mod url;
+mod validate;
+mod variant;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+pub use bounded::{CastBudget, CastLimits, TryCastFromBounded};
+pub use buffer::TryCastFromIn;
+pub use casted::Casted;
+pub use chain::{error_chain, find_all_cast, find_cast, find_cast_from, ErrorChain};
+pub use chunks::Chunks;
+pub use cow::TryCastIntoCow;
+#[cfg(feature = "decimal")]
+pub use decimal::Exact;
+#[cfg(feature = "derive")]
+pub use derive::{AsType, Cast, Flag, VariantName};
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+#[doc(hidden)]
+pub use document::{
+    can_decode_map as __can_decode_map, can_decode_seq as __can_decode_seq,
+    decode_f32 as __decode_f32, decode_f64 as __decode_f64, decode_int as __decode_int,
+    decode_map as __decode_map, decode_seq as __decode_seq,
+};
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+pub use document::{Decoded, Document, FormatCastError, ParseError};
+pub use drain::CastDrain;
+pub use each::Each;
+#[cfg(feature = "either")]
+pub use either::{CastEither, LeftType, RightType};
+pub use error::CastError;
+pub use extract::{Prefix, Suffix};
+#[cfg(feature = "ffi")]
+pub use ffi::Ffi;
+pub use field::{FieldError, FieldErrors, TryCastFromFields};
+pub use flags::{Flag, Flags};
+pub use float::{Finite, NanAsNone, NonFiniteAsNone, NotNan};
+#[cfg(feature = "futures")]
+pub use future::{AsyncTryCastFrom, AsyncTryCastInto};
+pub use iter::{CastEach, CastIter, CastPairs, MatchEach, TryCastEach};
+#[cfg(feature = "json")]
+pub use json::{JsonCastError, TryCastFromJson};
+pub use map::{
+    FromPairs, InsertPairs, KeyConflict, KeyConflictError, MapCastError, Pairs, UniqueKeys,
+};
+pub use nested::{try_fold_nested, Nested};
+pub use option::Flatten;
+pub use parse::{AutoRadix, Binary, FromStrRadix, Hex, Octal, Parsed, Radix};
+pub use pipeline::{CastPipeline, PipelineError};
+pub use result::{ErrType, OkType};
+pub use targets::CastTargets;
+#[cfg(feature = "toml")]
+pub use toml::{TomlCastError, TryCastFromToml};
+pub use validate::{NonEmpty, Predicate, Validated};
+pub use variant::VariantName;
+#[cfg(feature = "yaml")]
+pub use yaml::{TryCastFromYaml, YamlCastError};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use super::validate::validate;
+
+    /// Describe the value of a source field which failed to cast, for the `Cast` derive macro.
+    pub fn found<T: std::fmt::Debug>(value: &T) -> String {
+        format!("{value:?}")
+    }
+}
+
 /// Conversion methods from a container type (such as an `enum`) and a target type `T`.
 pub trait AsType<T>: From<T> {
     /// Borrow this instance as an instance of `T` if possible.
@@ -15,6 +160,76 @@ pub trait AsType<T>: From<T> {
 
     /// Convert this instance into an instance of `T` if possible.
     fn into_type(self) -> Option<T>;
+
+    /// Borrow this instance mutably as an instance of `T`, first replacing it with
+    /// `Self::from(default)` if it is not already an instance of `T`.
+    fn get_or_insert_type(&mut self, default: T) -> &mut T {
+        self.get_or_insert_type_with(|| default)
+    }
+
+    /// Borrow this instance mutably as an instance of `T`, first replacing it with
+    /// `Self::from(default())` if it is not already an instance of `T`.
+    fn get_or_insert_type_with<F: FnOnce() -> T>(&mut self, default: F) -> &mut T {
+        if self.as_type().is_none() {
+            *self = Self::from(default());
+        }
+
+        self.as_type_mut().expect("variant")
+    }
+}
+
+impl<T> AsType<T> for Option<T> {
+    fn as_type(&self) -> Option<&T> {
+        self.as_ref()
+    }
+
+    fn as_type_mut(&mut self) -> Option<&mut T> {
+        self.as_mut()
+    }
+
+    fn into_type(self) -> Option<T> {
+        self
+    }
+}
+
+/// Borrow the contents of `arc` mutably as an instance of `T`, if possible, cloning the contents
+/// first (with [`Arc::make_mut`](std::sync::Arc::make_mut)) only if they match `T` and are shared.
+/// Example:
+/// ```
+/// use std::sync::Arc;
+/// use safecast::{arc_as_type_mut, as_type};
+///
+/// #[derive(Clone)]
+/// enum Node {
+///     Leaf(u64),
+///     Branch(Vec<Node>),
+/// }
+///
+/// as_type!(Node, Leaf, u64);
+///
+/// let mut tree = Arc::new(Node::Leaf(1));
+/// let snapshot = tree.clone();
+///
+/// *arc_as_type_mut::<u64, _>(&mut tree).unwrap() += 1;
+/// assert!(matches!(*tree, Node::Leaf(2)));
+/// assert!(matches!(*snapshot, Node::Leaf(1)));
+/// ```
+pub fn arc_as_type_mut<T, E: AsType<T> + Clone>(arc: &mut std::sync::Arc<E>) -> Option<&mut T> {
+    if arc.as_type().is_some() {
+        std::sync::Arc::make_mut(arc).as_type_mut()
+    } else {
+        None
+    }
+}
+
+/// Borrow the contents of `rc` mutably as an instance of `T`, if possible, cloning the contents
+/// first (with [`Rc::make_mut`](std::rc::Rc::make_mut)) only if they match `T` and are shared.
+pub fn rc_as_type_mut<T, E: AsType<T> + Clone>(rc: &mut std::rc::Rc<E>) -> Option<&mut T> {
+    if rc.as_type().is_some() {
+        std::rc::Rc::make_mut(rc).as_type_mut()
+    } else {
+        None
+    }
 }
 
 /// Automatically implement `From` and `AsType` for an enum variant.
@@ -94,6 +309,119 @@ macro_rules! as_type {
     };
 }
 
+/// Implement `CastFrom` between every pair of a set of interchangeable types, by casting through
+/// a common representation type. Each type in the set must implement `CastFrom` (or `From`) into
+/// and from the representation type.
+/// Example:
+/// ```
+/// use safecast::{cast_all, CastFrom};
+///
+/// // each temperature is represented in kelvin
+/// struct Celsius(f64);
+/// struct Fahrenheit(f64);
+/// struct Kelvin(f64);
+///
+/// impl CastFrom<f64> for Celsius {
+///     fn cast_from(k: f64) -> Self {
+///         Self(k - 273.15)
+///     }
+/// }
+///
+/// impl CastFrom<Celsius> for f64 {
+///     fn cast_from(c: Celsius) -> Self {
+///         c.0 + 273.15
+///     }
+/// }
+///
+/// impl CastFrom<f64> for Fahrenheit {
+///     fn cast_from(k: f64) -> Self {
+///         Self((k - 273.15) * 1.8 + 32.)
+///     }
+/// }
+///
+/// impl CastFrom<Fahrenheit> for f64 {
+///     fn cast_from(f: Fahrenheit) -> Self {
+///         (f.0 - 32.) / 1.8 + 273.15
+///     }
+/// }
+///
+/// impl CastFrom<f64> for Kelvin {
+///     fn cast_from(k: f64) -> Self {
+///         Self(k)
+///     }
+/// }
+///
+/// impl CastFrom<Kelvin> for f64 {
+///     fn cast_from(k: Kelvin) -> Self {
+///         k.0
+///     }
+/// }
+///
+/// cast_all!(Celsius, Fahrenheit, Kelvin via f64);
+///
+/// assert_eq!(Fahrenheit::cast_from(Celsius(100.)).0, 212.);
+/// assert_eq!(Celsius::cast_from(Kelvin(0.)).0, -273.15);
+/// ```
+#[macro_export]
+macro_rules! cast_all {
+    ($($t:ident),+ via $repr:ty) => {
+        $crate::cast_all!(@each $repr; $($t),+);
+    };
+    (@each $repr:ty; $t:ident) => {};
+    (@each $repr:ty; $t:ident, $($rest:ident),+) => {
+        $(
+            $crate::cast_all!(@pair $repr; $t, $rest);
+            $crate::cast_all!(@pair $repr; $rest, $t);
+        )+
+
+        $crate::cast_all!(@each $repr; $($rest),+);
+    };
+    (@pair $repr:ty; $from:ident, $to:ident) => {
+        impl $crate::CastFrom<$from> for $to {
+            fn cast_from(value: $from) -> Self {
+                let repr = <$repr as $crate::CastFrom<$from>>::cast_from(value);
+                <$to as $crate::CastFrom<$repr>>::cast_from(repr)
+            }
+        }
+    };
+}
+
+/// Cast `value` into `T` and bind the result, if possible, or else evaluate the `else` block.
+///
+/// `value` is only borrowed by `can_cast_from` to decide which branch to take, so it's moved into
+/// `opt_cast_from` only on success, and is still available in the `else` block.
+/// Example:
+/// ```
+/// use safecast::{if_cast, Parsed};
+///
+/// let input = "42";
+///
+/// let n = if_cast!(input as Parsed<u8> => |n| { n.0 } else { 0 });
+/// assert_eq!(n, 42);
+///
+/// let input = String::from("x");
+/// let message = if_cast!(input as Parsed<u8> => |n| {
+///     format!("parsed {}", n.0)
+/// } else {
+///     format!("not a number: {}", input)
+/// });
+/// assert_eq!(message, "not a number: x");
+/// ```
+#[macro_export]
+macro_rules! if_cast {
+    ($value:ident as $t:ty => |$binding:pat_param| $then:block else $otherwise:block) => {
+        if <$t as $crate::TryCastFrom<_>>::can_cast_from(&$value) {
+            let $binding: $t = $crate::cast_after_check($value);
+            $then
+        } else {
+            $otherwise
+        }
+    };
+    ($value:ident as $t:ty => |$binding:pat_param| $then:block) => {
+        $crate::if_cast!($value as $t => |$binding| $then else {})
+    };
+}
+
 /// Trait for defining a cast operation from some source type `T`.
 /// Analogous to [`From`].
 /// The inverse of [`CastInto`].
@@ -129,7 +457,8 @@ impl<T, F: CastFrom<T>> CastInto<F> for T {
 /// Trait for defining a cast operation when the source type cannot always be cast to the
 /// destination type. Defines a `can_cast_from` method which borrows the source value, allowing
 /// for pattern matching without moving the value. When `can_cast_from` returns `true`, calling
-/// `opt_cast_from` *must* return `Some(...)`, otherwise `try_cast_from` may panic.
+/// `opt_cast_from` *must* return `Some(...)`, otherwise `try_cast_from` may panic. With
+/// `debug_assertions` enabled, the panic message names the source and target types.
 ///
 /// Analogous to [`TryFrom`].
 /// The inverse of [`TryCastInto`].
@@ -145,8 +474,14 @@ pub trait TryCastFrom<T>: Sized {
     /// Returns `Ok(Self)` if the source value can be cast into `Self`, otherwise calls `on_err`.
     fn try_cast_from<Err, OnErr: FnOnce(&T) -> Err>(value: T, on_err: OnErr) -> Result<Self, Err> {
         if Self::can_cast_from(&value) {
-            Ok(Self::opt_cast_from(value).unwrap())
+            #[cfg(feature = "metrics")]
+            metrics::record::<T, Self>(true);
+
+            Ok(expect_cast::<T, Self>(Self::opt_cast_from(value)))
         } else {
+            #[cfg(feature = "metrics")]
+            metrics::record::<T, Self>(false);
+
             Err(on_err(&value))
         }
     }
@@ -154,7 +489,8 @@ pub trait TryCastFrom<T>: Sized {
 /// Trait for defining a cast operation when the destination type cannot always be cast from the
 /// source type. Defines a `can_cast_into` method which borrows `self`, allowing for pattern
 /// matching without moving `self`. If `can_cast_into` returns `true`, then calling
-/// `opt_cast_into` *must* return `Some(...)`, otherwise `try_cast_into` may panic.
+/// `opt_cast_into` *must* return `Some(...)`, otherwise `try_cast_into` may panic. With
+/// `debug_assertions` enabled, the panic message names the source and target types.
 ///
 /// Analogous to [`TryFrom`].
 /// The inverse of [`TryCastInto`].
@@ -170,13 +506,41 @@ pub trait TryCastInto<T>: Sized {
     /// Returns `Ok(T)` if `self` can be cast into `T`, otherwise calls `on_err`.
     fn try_cast_into<Err, OnErr: FnOnce(&Self) -> Err>(self, on_err: OnErr) -> Result<T, Err> {
         if self.can_cast_into() {
-            Ok(self.opt_cast_into().unwrap())
+            #[cfg(feature = "metrics")]
+            metrics::record::<Self, T>(true);
+
+            Ok(expect_cast::<Self, T>(self.opt_cast_into()))
         } else {
+            #[cfg(feature = "metrics")]
+            metrics::record::<Self, T>(false);
+
             Err(on_err(&self))
         }
     }
 }
 
+/// Unwrap the result of an `opt_cast_*` call which followed a successful `can_cast_*` check.
+/// With `debug_assertions` enabled, a violation of this contract panics with the names of the
+/// source and target types, so that the faulty implementation can be found.
+#[track_caller]
+pub(crate) fn expect_cast<F: ?Sized, T>(cast: Option<T>) -> T {
+    debug_assert!(
+        cast.is_some(),
+        "TryCastFrom contract violated: {target} can cast from {source} according to \
+        can_cast_from, but opt_cast_from returned None",
+        source = std::any::type_name::<F>(),
+        target = std::any::type_name::<T>(),
+    );
+
+    cast.expect("cast")
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn cast_after_check<F, T: TryCastFrom<F>>(value: F) -> T {
+    expect_cast::<F, T>(T::opt_cast_from(value))
+}
+
 impl<F, T: CastFrom<F>> TryCastFrom<F> for T {
     fn can_cast_from(_: &F) -> bool {
         true
@@ -204,10 +568,143 @@ pub trait Match: Sized {
     fn matches<T: TryCastFrom<Self>>(&self) -> bool {
         T::can_cast_from(self)
     }
+
+    /// Returns `true` if `self` can be cast into the target type `T` within the given `limits`.
+    fn matches_bounded<T: TryCastFromBounded<Self>>(&self, limits: CastLimits) -> bool {
+        let mut budget = CastBudget::new(limits);
+        budget.visit() && T::can_cast_from_bounded(self, &mut budget)
+    }
 }
 
 impl<F> Match for F {}
 
+/// Umbrella trait for a type which round-trips through a representation type `Repr`, such as
+/// a dynamic value `enum`: it can always be cast into `Repr`, and `Repr` can be cast back into it
+/// when possible. Do not implement this trait; it's implemented automatically.
+///
+/// Example:
+/// ```
+/// use safecast::{as_type, AsType, CastInto, Castable, TryCastFrom};
+///
+/// enum Value {
+///     Int(u64),
+///     String(String),
+/// }
+///
+/// as_type!(Value, Int, u64);
+/// as_type!(Value, String, String);
+///
+/// impl TryCastFrom<Value> for u64 {
+///     fn can_cast_from(value: &Value) -> bool {
+///         AsType::<u64>::as_type(value).is_some()
+///     }
+///
+///     fn opt_cast_from(value: Value) -> Option<Self> {
+///         value.into_type()
+///     }
+/// }
+///
+/// fn round_trip<T: Castable<Value>>(value: T) -> Option<T> {
+///     let value: Value = value.cast_into();
+///     T::opt_cast_from(value)
+/// }
+///
+/// assert_eq!(round_trip(1u64), Some(1));
+/// ```
+pub trait Castable<Repr>: TryCastFrom<Repr> + CastInto<Repr> {}
+
+impl<Repr, T: TryCastFrom<Repr> + CastInto<Repr>> Castable<Repr> for T {}
+
+/// Trait for a cast operation from a borrowed source value `&T` with any lifetime.
+/// Use `U: CastFromRef<T>` as a bound instead of the higher-ranked `for<'a> U: CastFrom<&'a T>`.
+/// Do not implement this trait; implement `CastFrom<&'a T>` for every lifetime `'a` instead.
+pub trait CastFromRef<T: ?Sized> {
+    /// Cast a borrowed instance of `T` into an instance of `Self`.
+    fn cast_from_ref(value: &T) -> Self;
+}
+
+impl<T: ?Sized, U> CastFromRef<T> for U
+where
+    for<'a> U: CastFrom<&'a T>,
+{
+    fn cast_from_ref(value: &T) -> Self {
+        U::cast_from(value)
+    }
+}
+
+/// Trait for a fallible cast operation from a borrowed source value `&T` with any lifetime.
+/// Use `U: TryCastFromRef<T>` as a bound instead of the higher-ranked
+/// `for<'a> U: TryCastFrom<&'a T>`.
+/// Do not implement this trait; implement `TryCastFrom<&'a T>` for every lifetime `'a` instead.
+///
+/// Example:
+/// ```
+/// use safecast::{TryCastFrom, TryCastFromRef};
+///
+/// struct Name(String);
+///
+/// impl<'a> TryCastFrom<&'a str> for Name {
+///     fn can_cast_from(value: &&'a str) -> bool {
+///         !value.is_empty()
+///     }
+///
+///     fn opt_cast_from(value: &'a str) -> Option<Self> {
+///         if value.is_empty() {
+///             None
+///         } else {
+///             Some(Self(value.to_string()))
+///         }
+///     }
+/// }
+///
+/// fn names<T: TryCastFromRef<str>>(line: &str) -> Option<Vec<T>> {
+///     let line = line.to_string();
+///     line.split(',').map(T::opt_cast_from_ref).collect()
+/// }
+///
+/// assert!(names::<Name>("a,b").is_some());
+/// assert!(names::<Name>("a,,b").is_none());
+/// ```
+pub trait TryCastFromRef<T: ?Sized>: Sized {
+    /// Test if `value` can be cast into `Self`.
+    fn can_cast_from_ref(value: &T) -> bool;
+
+    /// Returns `Some(Self)` if the borrowed source value can be cast into `Self`, otherwise `None`.
+    fn opt_cast_from_ref(value: &T) -> Option<Self>;
+
+    /// Returns `Ok(Self)` if the borrowed source value can be cast into `Self`, otherwise calls
+    /// `on_err`.
+    fn try_cast_from_ref<Err, OnErr: FnOnce(&T) -> Err>(
+        value: &T,
+        on_err: OnErr,
+    ) -> Result<Self, Err> {
+        if Self::can_cast_from_ref(value) {
+            #[cfg(feature = "metrics")]
+            metrics::record::<&T, Self>(true);
+
+            Ok(expect_cast::<&T, Self>(Self::opt_cast_from_ref(value)))
+        } else {
+            #[cfg(feature = "metrics")]
+            metrics::record::<&T, Self>(false);
+
+            Err(on_err(value))
+        }
+    }
+}
+
+impl<T: ?Sized, U> TryCastFromRef<T> for U
+where
+    for<'a> U: TryCastFrom<&'a T>,
+{
+    fn can_cast_from_ref(value: &T) -> bool {
+        U::can_cast_from(&value)
+    }
+
+    fn opt_cast_from_ref(value: &T) -> Option<Self> {
+        U::opt_cast_from(value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -257,12 +754,185 @@ mod tests {
         }
     }
 
+    impl TryCastFrom<FooBar> for Bar {
+        fn can_cast_from(foo_bar: &FooBar) -> bool {
+            AsType::<Bar>::as_type(foo_bar).is_some()
+        }
+
+        fn opt_cast_from(foo_bar: FooBar) -> Option<Self> {
+            foo_bar.into_type()
+        }
+    }
+
+    #[test]
+    fn test_get_or_insert_type() {
+        let mut foo_bar = FooBar::Foo(Foo { a: 1 });
+        foo_bar.get_or_insert_type(Bar { b: 2 }).b += 1;
+        assert_eq!(AsType::<Bar>::as_type(&foo_bar), Some(&Bar { b: 3 }));
+
+        let bar = foo_bar.get_or_insert_type_with(|| panic!("variant already matches"));
+        assert_eq!(bar, &Bar { b: 3 });
+    }
+
+    #[test]
+    fn test_cast_shared_str() {
+        use std::rc::Rc;
+        use std::sync::Arc;
+
+        fn intern<T: CastFrom<String>>(s: &str) -> T {
+            T::cast_from(s.to_string())
+        }
+
+        let shared: Arc<str> = intern("a");
+        let local: Rc<str> = intern("b");
+        let boxed: Box<str> = intern("c");
+
+        assert_eq!(String::cast_from(&*shared), "a");
+        assert_eq!(String::cast_from(&*local), "b");
+        assert_eq!(Arc::<str>::cast_from(boxed.clone()).as_ref(), "c");
+        assert_eq!(String::cast_from(boxed), "c");
+    }
+
+    #[test]
+    fn test_as_type_option() {
+        fn first<C: AsType<u8>>(items: &[C]) -> Option<&u8> {
+            items.iter().find_map(AsType::as_type)
+        }
+
+        assert_eq!(first(&[None, Some(2u8), Some(3)]), Some(&2));
+
+        let mut item: Option<u8> = None;
+        *item.get_or_insert_type(1) += 1;
+        assert_eq!(item.into_type(), Some(2));
+    }
+
+    #[test]
+    fn test_cast_from_infallible() {
+        fn absorb<E, T: CastFrom<E>>(result: Result<u8, E>) -> Result<u8, T> {
+            result.map_err(T::cast_from)
+        }
+
+        let result: Result<u8, std::convert::Infallible> = Ok(1);
+        assert_eq!(absorb::<_, crate::CastError>(result), Ok(1));
+    }
+
+    #[test]
+    fn test_arc_as_type_mut() {
+        use std::rc::Rc;
+        use std::sync::Arc;
+
+        let mut shared = Arc::new(Some(Bar { b: 1 }));
+        let other = shared.clone();
+        arc_as_type_mut::<Bar, _>(&mut shared).unwrap().b = 2;
+        assert_eq!(*other, Some(Bar { b: 1 }));
+        assert_eq!(*shared, Some(Bar { b: 2 }));
+
+        let mut empty = Arc::new(None::<Bar>);
+        let other = empty.clone();
+        assert!(arc_as_type_mut::<Bar, _>(&mut empty).is_none());
+        assert!(Arc::ptr_eq(&empty, &other));
+
+        let mut local = Rc::new(Some(Bar { b: 3 }));
+        rc_as_type_mut::<Bar, _>(&mut local).unwrap().b = 4;
+        assert_eq!(*local, Some(Bar { b: 4 }));
+    }
+
     #[test]
     fn test_cast() {
         let foo = Foo { a: 1 };
         assert_eq!(Bar::cast_from(foo), Bar { b: 1 })
     }
 
+    #[derive(Debug, Eq, PartialEq)]
+    struct Meters(u64);
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Kilometers(u64);
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Millimeters(u64);
+
+    impl From<u64> for Meters {
+        fn from(mm: u64) -> Self {
+            Self(mm / 1_000)
+        }
+    }
+
+    impl From<Meters> for u64 {
+        fn from(m: Meters) -> Self {
+            m.0 * 1_000
+        }
+    }
+
+    impl From<u64> for Kilometers {
+        fn from(mm: u64) -> Self {
+            Self(mm / 1_000_000)
+        }
+    }
+
+    impl From<Kilometers> for u64 {
+        fn from(km: Kilometers) -> Self {
+            km.0 * 1_000_000
+        }
+    }
+
+    impl From<u64> for Millimeters {
+        fn from(mm: u64) -> Self {
+            Self(mm)
+        }
+    }
+
+    impl From<Millimeters> for u64 {
+        fn from(mm: Millimeters) -> Self {
+            mm.0
+        }
+    }
+
+    cast_all!(Meters, Kilometers, Millimeters via u64);
+
+    #[test]
+    fn test_cast_all() {
+        assert_eq!(Meters::cast_from(Kilometers(2)), Meters(2_000));
+        assert_eq!(Millimeters::cast_from(Meters(2)), Millimeters(2_000));
+        assert_eq!(Kilometers::cast_from(Millimeters(3_000_000)), Kilometers(3));
+        assert_eq!(Kilometers::cast_from(Meters(5_000)), Kilometers(5));
+    }
+
+    #[test]
+    fn test_if_cast() {
+        let bar = Bar { b: 0 };
+        let b = if_cast!(bar as Baz => |baz| { baz.bar.b + 10 } else { bar.b });
+        assert_eq!(b, 10);
+
+        let bar = Bar { b: 1 };
+        let b = if_cast!(bar as Baz => |baz| { baz.bar.b + 10 } else { bar.b });
+        assert_eq!(b, 1);
+
+        let mut cast = false;
+        let bar = Bar { b: 0 };
+        if_cast!(bar as Baz => |_baz| { cast = true });
+        assert!(cast);
+    }
+
+    struct Inconsistent;
+
+    impl TryCastFrom<u8> for Inconsistent {
+        fn can_cast_from(_: &u8) -> bool {
+            true
+        }
+
+        fn opt_cast_from(_: u8) -> Option<Self> {
+            None
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "TryCastFrom contract violated")]
+    fn test_contract_violation() {
+        let _ = Inconsistent::try_cast_from(1u8, |_| ());
+    }
+
     #[test]
     fn test_matches() {
         let bar0 = Bar { b: 0 };
@@ -283,6 +953,30 @@ mod tests {
         assert!(Baz::try_cast_from(bar1, |_| CastError).is_err());
     }
 
+    #[test]
+    fn test_castable() {
+        fn round_trip<T: Castable<FooBar>>(value: T) -> Option<T> {
+            T::opt_cast_from(value.cast_into())
+        }
+
+        let bar = Bar { b: 1 };
+        assert_eq!(round_trip(bar), Some(bar));
+    }
+
+    #[test]
+    fn test_cast_from_ref() {
+        fn cast_all<T: CastFromRef<str>>(values: &[String]) -> Vec<T> {
+            values.iter().map(|value| T::cast_from_ref(value)).collect()
+        }
+
+        let values = ["a".to_string(), "b".to_string()];
+        assert_eq!(cast_all::<String>(&values), values);
+        assert_eq!(
+            <Box<str> as TryCastFromRef<str>>::opt_cast_from_ref("a"),
+            Some("a".into())
+        );
+    }
+
     #[test]
     fn test_as_type_macro() {
         let bar = Bar { b: 0 };
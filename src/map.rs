@@ -0,0 +1,590 @@
+//! Casting between lists of key-value [`Pairs`] and maps.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::convert::Infallible;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::ops::{Deref, DerefMut};
+
+use super::{CastBudget, CastError, TryCastFrom, TryCastFromBounded};
+
+/// A map type which can be collected from an iterator of key-value pairs.
+pub trait FromPairs: FromIterator<(Self::Key, Self::Value)> {
+    /// The type of key in this map.
+    type Key;
+
+    /// The type of value in this map.
+    type Value;
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> FromPairs for HashMap<K, V, S> {
+    type Key = K;
+    type Value = V;
+}
+
+impl<K: Ord, V> FromPairs for BTreeMap<K, V> {
+    type Key = K;
+    type Value = V;
+}
+
+/// An error which occurs when an entry of a map cannot be cast, including its original key.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MapCastError<K> {
+    key: K,
+    error: CastError,
+}
+
+impl<K> MapCastError<K> {
+    /// Construct a new error describing the failure to cast the entry at `key`.
+    pub fn new(key: K, error: CastError) -> Self {
+        Self { key, error }
+    }
+
+    /// Borrow the key of the entry which could not be cast.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Return the key of the entry which could not be cast.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// The [`CastError`] describing the key or value which could not be cast.
+    pub fn error(&self) -> &CastError {
+        &self.error
+    }
+}
+
+impl<K: fmt::Debug> fmt::Display for MapCastError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cannot cast entry {:?}: {}", self.key, self.error)
+    }
+}
+
+impl<K: fmt::Debug> std::error::Error for MapCastError<K> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<K> From<Infallible> for MapCastError<K> {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+/// A map into which entries can be inserted one at a time, as when casting entries with a
+/// [`KeyConflict`] policy.
+pub trait InsertPairs: FromPairs + Default {
+    /// Returns `true` if this map contains an entry at `key`.
+    fn contains_key(&self, key: &Self::Key) -> bool;
+
+    /// Insert `value` at `key`, replacing any existing value.
+    fn insert(&mut self, key: Self::Key, value: Self::Value);
+
+    /// Remove and return the value at `key`, if any.
+    fn remove(&mut self, key: &Self::Key) -> Option<Self::Value>;
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> InsertPairs for HashMap<K, V, S> {
+    fn contains_key(&self, key: &K) -> bool {
+        HashMap::contains_key(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        HashMap::remove(self, key)
+    }
+}
+
+impl<K: Ord, V> InsertPairs for BTreeMap<K, V> {
+    fn contains_key(&self, key: &K) -> bool {
+        BTreeMap::contains_key(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        BTreeMap::insert(self, key, value);
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        BTreeMap::remove(self, key)
+    }
+}
+
+/// How to resolve two entries whose keys are cast into the same key of the target map, used by
+/// [`CastPairs::try_cast_map_with`](crate::CastPairs::try_cast_map_with).
+pub enum KeyConflict<'a, V> {
+    /// Stop with a [`KeyConflictError::Conflict`] error.
+    Error,
+    /// Keep the value of the first entry, ignoring the rest.
+    KeepFirst,
+    /// Keep the value of the last entry, as collecting into a map does.
+    KeepLast,
+    /// Combine the existing value with the value of the new entry.
+    Merge(Box<dyn FnMut(V, V) -> V + 'a>),
+}
+
+impl<'a, V> KeyConflict<'a, V> {
+    /// Construct a policy to combine the existing value with each new value using `merge`.
+    pub fn merge<F: FnMut(V, V) -> V + 'a>(merge: F) -> Self {
+        Self::Merge(Box::new(merge))
+    }
+
+    pub(crate) fn insert<M>(&mut self, map: &mut M, key: M::Key, value: V) -> Result<(), M::Key>
+    where
+        M: InsertPairs<Value = V>,
+    {
+        match self {
+            Self::Error if map.contains_key(&key) => return Err(key),
+            Self::KeepFirst if map.contains_key(&key) => {}
+            Self::Merge(merge) => match map.remove(&key) {
+                Some(existing) => map.insert(key, merge(existing, value)),
+                None => map.insert(key, value),
+            },
+            _ => map.insert(key, value),
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, V> fmt::Debug for KeyConflict<'a, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Error => "Error",
+            Self::KeepFirst => "KeepFirst",
+            Self::KeepLast => "KeepLast",
+            Self::Merge(_) => "Merge",
+        })
+    }
+}
+
+/// An error which occurs when casting entries into a map with a [`KeyConflict`] policy.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KeyConflictError<K, K2> {
+    /// An entry could not be cast.
+    Cast(MapCastError<K>),
+    /// Two entries were cast into this same key, with the [`KeyConflict::Error`] policy.
+    Conflict(K2),
+}
+
+impl<K: fmt::Debug, K2: fmt::Debug> fmt::Display for KeyConflictError<K, K2> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Cast(cause) => fmt::Display::fmt(cause, f),
+            Self::Conflict(key) => write!(f, "more than one entry was cast into the key {:?}", key),
+        }
+    }
+}
+
+impl<K: fmt::Debug + 'static, K2: fmt::Debug> std::error::Error for KeyConflictError<K, K2> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Cast(cause) => Some(cause),
+            Self::Conflict(_) => None,
+        }
+    }
+}
+
+impl<K, K2> From<Infallible> for KeyConflictError<K, K2> {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+impl<K, K2> From<MapCastError<K>> for KeyConflictError<K, K2> {
+    fn from(cause: MapCastError<K>) -> Self {
+        Self::Cast(cause)
+    }
+}
+
+/// A list of key-value pairs, which can be cast into and from a map, casting each key and value.
+///
+/// Example:
+/// ```
+/// use std::collections::HashMap;
+/// use safecast::{Pairs, TryCastFrom};
+///
+/// let pairs = Pairs::from(vec![("a", 1u8), ("b", 2u8)]);
+/// let map = HashMap::<String, u64>::opt_cast_from(pairs).unwrap();
+/// assert_eq!(map["b"], 2);
+///
+/// let pairs = Pairs::<String, u128>::opt_cast_from(map).unwrap();
+/// assert_eq!(pairs.len(), 2);
+/// ```
+///
+/// When a key occurs more than once, the last value wins. To reject duplicate keys instead,
+/// cast into [`UniqueKeys`].
+///
+/// Casts between `Pairs` and maps also implement [`TryCastFromBounded`], counting each entry
+/// against the budget one level of nesting deeper than the map itself, then checking its key and
+/// value within what remains.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Pairs<K, V>(pub Vec<(K, V)>);
+
+impl<K, V> Pairs<K, V> {
+    /// Return the list of key-value pairs.
+    pub fn into_inner(self) -> Vec<(K, V)> {
+        self.0
+    }
+}
+
+impl<K, V> Deref for Pairs<K, V> {
+    type Target = Vec<(K, V)>;
+
+    fn deref(&self) -> &Vec<(K, V)> {
+        &self.0
+    }
+}
+
+impl<K, V> DerefMut for Pairs<K, V> {
+    fn deref_mut(&mut self) -> &mut Vec<(K, V)> {
+        &mut self.0
+    }
+}
+
+impl<K, V> From<Vec<(K, V)>> for Pairs<K, V> {
+    fn from(pairs: Vec<(K, V)>) -> Self {
+        Self(pairs)
+    }
+}
+
+impl<K, V> From<Pairs<K, V>> for Vec<(K, V)> {
+    fn from(pairs: Pairs<K, V>) -> Self {
+        pairs.0
+    }
+}
+
+/// A map which was cast from a list of [`Pairs`] containing no duplicate keys.
+///
+/// Distinct source keys which cast into equal keys are not detected; to handle these, use
+/// [`CastPairs::try_cast_map_with`](crate::CastPairs::try_cast_map_with).
+/// Example:
+/// ```
+/// use std::collections::BTreeMap;
+/// use safecast::{Pairs, TryCastFrom, UniqueKeys};
+///
+/// let pairs = Pairs::from(vec![("a", 1u32), ("b", 2u32)]);
+/// assert!(UniqueKeys::<BTreeMap<String, u64>>::can_cast_from(&pairs));
+///
+/// let pairs = Pairs::from(vec![("a", 1u32), ("a", 2u32)]);
+/// assert!(UniqueKeys::<BTreeMap<String, u64>>::opt_cast_from(pairs).is_none());
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct UniqueKeys<M>(pub M);
+
+impl<M> UniqueKeys<M> {
+    /// Return the map.
+    pub fn into_inner(self) -> M {
+        self.0
+    }
+}
+
+impl<M> Deref for UniqueKeys<M> {
+    type Target = M;
+
+    fn deref(&self) -> &M {
+        &self.0
+    }
+}
+
+fn can_cast_pairs<K, V, K2, V2>(pairs: &[(K, V)]) -> bool
+where
+    K2: TryCastFrom<K>,
+    V2: TryCastFrom<V>,
+{
+    pairs
+        .iter()
+        .all(|(key, value)| K2::can_cast_from(key) && V2::can_cast_from(value))
+}
+
+fn can_cast_pairs_bounded<'a, K, V, K2, V2, I>(pairs: I, budget: &mut CastBudget) -> bool
+where
+    K: 'a,
+    V: 'a,
+    K2: TryCastFromBounded<K>,
+    V2: TryCastFromBounded<V>,
+    I: IntoIterator<Item = (&'a K, &'a V)>,
+{
+    budget.descend(|budget| {
+        pairs.into_iter().all(|(key, value)| {
+            budget.visit()
+                && K2::can_cast_from_bounded(key, budget)
+                && V2::can_cast_from_bounded(value, budget)
+        })
+    })
+}
+
+fn opt_cast_pairs<K, V, K2, V2, M>(pairs: Vec<(K, V)>) -> Option<M>
+where
+    K2: TryCastFrom<K>,
+    V2: TryCastFrom<V>,
+    M: FromIterator<(K2, V2)>,
+{
+    pairs
+        .into_iter()
+        .map(|(key, value)| Some((K2::opt_cast_from(key)?, V2::opt_cast_from(value)?)))
+        .collect()
+}
+
+fn has_unique_hash_keys<K: Eq + Hash, V>(pairs: &[(K, V)]) -> bool {
+    let mut keys = HashSet::with_capacity(pairs.len());
+    pairs.iter().all(|(key, _)| keys.insert(key))
+}
+
+fn has_unique_ord_keys<K: Ord, V>(pairs: &[(K, V)]) -> bool {
+    let mut keys = BTreeSet::new();
+    pairs.iter().all(|(key, _)| keys.insert(key))
+}
+
+impl<K, V, K2, V2, S> TryCastFrom<Pairs<K, V>> for HashMap<K2, V2, S>
+where
+    K2: TryCastFrom<K> + Eq + Hash,
+    V2: TryCastFrom<V>,
+    S: BuildHasher + Default,
+{
+    fn can_cast_from(pairs: &Pairs<K, V>) -> bool {
+        can_cast_pairs::<K, V, K2, V2>(pairs)
+    }
+
+    fn opt_cast_from(pairs: Pairs<K, V>) -> Option<Self> {
+        opt_cast_pairs(pairs.0)
+    }
+}
+
+impl<K, V, K2, V2> TryCastFrom<Pairs<K, V>> for BTreeMap<K2, V2>
+where
+    K2: TryCastFrom<K> + Ord,
+    V2: TryCastFrom<V>,
+{
+    fn can_cast_from(pairs: &Pairs<K, V>) -> bool {
+        can_cast_pairs::<K, V, K2, V2>(pairs)
+    }
+
+    fn opt_cast_from(pairs: Pairs<K, V>) -> Option<Self> {
+        opt_cast_pairs(pairs.0)
+    }
+}
+
+impl<K, V, K2, V2, S> TryCastFrom<Pairs<K, V>> for UniqueKeys<HashMap<K2, V2, S>>
+where
+    K: Eq + Hash,
+    K2: TryCastFrom<K> + Eq + Hash,
+    V2: TryCastFrom<V>,
+    S: BuildHasher + Default,
+{
+    fn can_cast_from(pairs: &Pairs<K, V>) -> bool {
+        has_unique_hash_keys(pairs) && can_cast_pairs::<K, V, K2, V2>(pairs)
+    }
+
+    fn opt_cast_from(pairs: Pairs<K, V>) -> Option<Self> {
+        if has_unique_hash_keys(&pairs) {
+            opt_cast_pairs(pairs.0).map(Self)
+        } else {
+            None
+        }
+    }
+}
+
+impl<K, V, K2, V2> TryCastFrom<Pairs<K, V>> for UniqueKeys<BTreeMap<K2, V2>>
+where
+    K: Ord,
+    K2: TryCastFrom<K> + Ord,
+    V2: TryCastFrom<V>,
+{
+    fn can_cast_from(pairs: &Pairs<K, V>) -> bool {
+        has_unique_ord_keys(pairs) && can_cast_pairs::<K, V, K2, V2>(pairs)
+    }
+
+    fn opt_cast_from(pairs: Pairs<K, V>) -> Option<Self> {
+        if has_unique_ord_keys(&pairs) {
+            opt_cast_pairs(pairs.0).map(Self)
+        } else {
+            None
+        }
+    }
+}
+
+impl<K, V, K2, V2, S> TryCastFrom<HashMap<K, V, S>> for Pairs<K2, V2>
+where
+    K2: TryCastFrom<K>,
+    V2: TryCastFrom<V>,
+{
+    fn can_cast_from(map: &HashMap<K, V, S>) -> bool {
+        map.iter()
+            .all(|(key, value)| K2::can_cast_from(key) && V2::can_cast_from(value))
+    }
+
+    fn opt_cast_from(map: HashMap<K, V, S>) -> Option<Self> {
+        map.into_iter()
+            .map(|(key, value)| Some((K2::opt_cast_from(key)?, V2::opt_cast_from(value)?)))
+            .collect::<Option<Vec<_>>>()
+            .map(Self)
+    }
+}
+
+impl<K, V, K2, V2> TryCastFrom<BTreeMap<K, V>> for Pairs<K2, V2>
+where
+    K2: TryCastFrom<K>,
+    V2: TryCastFrom<V>,
+{
+    fn can_cast_from(map: &BTreeMap<K, V>) -> bool {
+        map.iter()
+            .all(|(key, value)| K2::can_cast_from(key) && V2::can_cast_from(value))
+    }
+
+    fn opt_cast_from(map: BTreeMap<K, V>) -> Option<Self> {
+        map.into_iter()
+            .map(|(key, value)| Some((K2::opt_cast_from(key)?, V2::opt_cast_from(value)?)))
+            .collect::<Option<Vec<_>>>()
+            .map(Self)
+    }
+}
+
+fn pair_refs<K, V>(pairs: &[(K, V)]) -> impl Iterator<Item = (&K, &V)> {
+    pairs.iter().map(|(key, value)| (key, value))
+}
+
+impl<K, V, K2, V2, S> TryCastFromBounded<Pairs<K, V>> for HashMap<K2, V2, S>
+where
+    K2: TryCastFromBounded<K> + Eq + Hash,
+    V2: TryCastFromBounded<V>,
+    S: BuildHasher + Default,
+{
+    fn can_cast_from_bounded(pairs: &Pairs<K, V>, budget: &mut CastBudget) -> bool {
+        can_cast_pairs_bounded::<K, V, K2, V2, _>(pair_refs(pairs), budget)
+    }
+}
+
+impl<K, V, K2, V2> TryCastFromBounded<Pairs<K, V>> for BTreeMap<K2, V2>
+where
+    K2: TryCastFromBounded<K> + Ord,
+    V2: TryCastFromBounded<V>,
+{
+    fn can_cast_from_bounded(pairs: &Pairs<K, V>, budget: &mut CastBudget) -> bool {
+        can_cast_pairs_bounded::<K, V, K2, V2, _>(pair_refs(pairs), budget)
+    }
+}
+
+impl<K, V, K2, V2, S> TryCastFromBounded<Pairs<K, V>> for UniqueKeys<HashMap<K2, V2, S>>
+where
+    K: Eq + Hash,
+    K2: TryCastFromBounded<K> + Eq + Hash,
+    V2: TryCastFromBounded<V>,
+    S: BuildHasher + Default,
+{
+    fn can_cast_from_bounded(pairs: &Pairs<K, V>, budget: &mut CastBudget) -> bool {
+        can_cast_pairs_bounded::<K, V, K2, V2, _>(pair_refs(pairs), budget)
+            && has_unique_hash_keys(pairs)
+    }
+}
+
+impl<K, V, K2, V2> TryCastFromBounded<Pairs<K, V>> for UniqueKeys<BTreeMap<K2, V2>>
+where
+    K: Ord,
+    K2: TryCastFromBounded<K> + Ord,
+    V2: TryCastFromBounded<V>,
+{
+    fn can_cast_from_bounded(pairs: &Pairs<K, V>, budget: &mut CastBudget) -> bool {
+        can_cast_pairs_bounded::<K, V, K2, V2, _>(pair_refs(pairs), budget)
+            && has_unique_ord_keys(pairs)
+    }
+}
+
+impl<K, V, K2, V2, S> TryCastFromBounded<HashMap<K, V, S>> for Pairs<K2, V2>
+where
+    K2: TryCastFromBounded<K>,
+    V2: TryCastFromBounded<V>,
+{
+    fn can_cast_from_bounded(map: &HashMap<K, V, S>, budget: &mut CastBudget) -> bool {
+        can_cast_pairs_bounded::<K, V, K2, V2, _>(map, budget)
+    }
+}
+
+impl<K, V, K2, V2> TryCastFromBounded<BTreeMap<K, V>> for Pairs<K2, V2>
+where
+    K2: TryCastFromBounded<K>,
+    V2: TryCastFromBounded<V>,
+{
+    fn can_cast_from_bounded(map: &BTreeMap<K, V>, budget: &mut CastBudget) -> bool {
+        can_cast_pairs_bounded::<K, V, K2, V2, _>(map, budget)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CastLimits, CastPairs, Match, Parsed};
+
+    #[test]
+    fn test_pairs_into_map() {
+        let pairs = Pairs::from(vec![("a", 1u8), ("b", 2u8), ("a", 3u8)]);
+        assert!(pairs.matches::<HashMap<String, u16>>());
+        assert!(!pairs.matches::<UniqueKeys<HashMap<String, u16>>>());
+        assert!(!pairs.matches::<UniqueKeys<BTreeMap<String, u16>>>());
+
+        let map = BTreeMap::<String, u16>::opt_cast_from(pairs).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["a"], 3);
+    }
+
+    #[test]
+    fn test_map_into_pairs() {
+        let map = BTreeMap::from([("a", 1u8), ("b", 2u8)]);
+        let pairs = Pairs::<String, u64>::opt_cast_from(map).unwrap();
+        assert_eq!(
+            pairs.into_inner(),
+            vec![("a".to_string(), 1), ("b".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_pairs_bounded() {
+        let pairs = Pairs::from(vec![("a", 1u8), ("b", 2u8)]);
+        assert!(pairs.matches_bounded::<HashMap<String, u16>>(CastLimits::new(1, 3)));
+        assert!(!pairs.matches_bounded::<HashMap<String, u16>>(CastLimits::new(1, 2)));
+        assert!(!pairs.matches_bounded::<BTreeMap<String, u16>>(CastLimits::new(0, 3)));
+
+        let pairs = Pairs::from(vec![("a", 1u8), ("a", 2u8)]);
+        assert!(!pairs.matches_bounded::<UniqueKeys<BTreeMap<String, u16>>>(CastLimits::new(1, 3)));
+
+        let map = BTreeMap::from([("a", 1u8)]);
+        assert!(map.matches_bounded::<Pairs<String, u64>>(CastLimits::new(1, 2)));
+    }
+
+    #[test]
+    fn test_key_conflict() {
+        let mut policy = KeyConflict::merge(|a: u32, b| a + b);
+        let mut map = BTreeMap::new();
+        policy.insert(&mut map, "a", 1).unwrap();
+        policy.insert(&mut map, "a", 2).unwrap();
+        assert_eq!(map["a"], 3);
+
+        let mut policy = KeyConflict::KeepFirst;
+        policy.insert(&mut map, "a", 4).unwrap();
+        assert_eq!(map["a"], 3);
+
+        let mut policy = KeyConflict::Error;
+        assert_eq!(policy.insert(&mut map, "a", 5), Err("a"));
+        policy.insert(&mut map, "b", 5).unwrap();
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_key_conflict_error_source() {
+        use std::error::Error;
+
+        let pairs = [("a", "300")].into_iter();
+        let error = pairs
+            .try_cast_map_with::<BTreeMap<String, Parsed<u8>>>(KeyConflict::Error)
+            .unwrap_err();
+
+        let cause = error.source().expect("cause");
+        assert_eq!(cause.to_string(), error.to_string());
+        assert!(cause.source().is_some());
+    }
+}
@@ -0,0 +1,171 @@
+//! Counters of the successes and failures of fallible casts, by source and target type.
+//!
+//! Install a [`CastRecorder`] with [`set_recorder`] to observe every call to the provided
+//! `try_cast_from`, `try_cast_into`, and `try_cast_from_ref` methods (and their async
+//! counterparts), each step of a [`CastPipeline`](crate::CastPipeline), and each key and value
+//! cast by [`CastPairs::try_cast_map`](crate::CastPairs::try_cast_map) or `try_cast_map_with`.
+//! [`CastCounters`] is a simple in-memory recorder; implement [`CastRecorder`] to forward counts
+//! to a metrics system instead.
+//!
+//! Calls to `opt_cast_from`, `opt_cast_into`, `can_cast_from`, and `can_cast_into` are not
+//! counted, nor are the methods built on them, like [`Match::matches`](crate::Match::matches),
+//! since they are also used internally to check a cast before making it.
+//!
+//! Example:
+//! ```
+//! use safecast::metrics::{set_recorder, CastCounters};
+//! use safecast::{Parsed, TryCastFrom};
+//!
+//! static COUNTERS: CastCounters = CastCounters::new();
+//! set_recorder(&COUNTERS).unwrap();
+//!
+//! assert!(Parsed::<u8>::try_cast_from("1", |_| ()).is_ok());
+//! assert!(Parsed::<u8>::try_cast_from("x", |_| ()).is_err());
+//!
+//! let (successes, failures) = COUNTERS.get::<&str, Parsed<u8>>();
+//! assert_eq!((successes, failures), (1, 1));
+//! ```
+
+use std::any::type_name;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+type Counts = BTreeMap<(&'static str, &'static str), (u64, u64)>;
+
+static RECORDER: OnceLock<&'static dyn CastRecorder> = OnceLock::new();
+
+/// A receiver of the outcome of each fallible cast.
+pub trait CastRecorder: Send + Sync {
+    /// Record that a value of type `source_type` was (or was not) cast into `target_type`.
+    fn record(&self, source_type: &'static str, target_type: &'static str, success: bool);
+}
+
+/// An error which occurs when a [`CastRecorder`] has already been installed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SetRecorderError;
+
+impl fmt::Display for SetRecorderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a cast recorder has already been installed")
+    }
+}
+
+impl std::error::Error for SetRecorderError {}
+
+/// Install the global [`CastRecorder`]. This can only be done once per process.
+pub fn set_recorder(recorder: &'static dyn CastRecorder) -> Result<(), SetRecorderError> {
+    RECORDER.set(recorder).map_err(|_| SetRecorderError)
+}
+
+pub(crate) fn record<F: ?Sized, T: ?Sized>(success: bool) {
+    if let Some(recorder) = RECORDER.get() {
+        recorder.record(type_name::<F>(), type_name::<T>(), success);
+    }
+}
+
+/// An in-memory [`CastRecorder`] which counts the successes and failures of each pair of types.
+#[derive(Debug, Default)]
+pub struct CastCounters {
+    counts: Mutex<Counts>,
+}
+
+impl CastCounters {
+    /// Construct a new set of counters, all zero.
+    pub const fn new() -> Self {
+        Self {
+            counts: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// The number of successful and failed casts from `F` into `T`.
+    pub fn get<F: ?Sized, T: ?Sized>(&self) -> (u64, u64) {
+        let counts = self.counts.lock().unwrap_or_else(PoisonError::into_inner);
+        let key = (type_name::<F>(), type_name::<T>());
+        counts.get(&key).copied().unwrap_or_default()
+    }
+
+    /// The number of successful and failed casts of every pair of source and target types which
+    /// has been recorded, sorted by source type name and then target type name.
+    pub fn snapshot(&self) -> Vec<(&'static str, &'static str, u64, u64)> {
+        let counts = self.counts.lock().unwrap_or_else(PoisonError::into_inner);
+
+        counts
+            .iter()
+            .map(|((source, target), (successes, failures))| {
+                (*source, *target, *successes, *failures)
+            })
+            .collect()
+    }
+}
+
+impl CastRecorder for CastCounters {
+    fn record(&self, source_type: &'static str, target_type: &'static str, success: bool) {
+        let mut counts = self.counts.lock().unwrap_or_else(PoisonError::into_inner);
+        let (successes, failures) = counts.entry((source_type, target_type)).or_default();
+
+        if success {
+            *successes += 1;
+        } else {
+            *failures += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_cast_counters() {
+        let counters = CastCounters::new();
+        counters.record("u64", "u8", true);
+        counters.record("u64", "u8", false);
+        counters.record("u64", "u8", false);
+        counters.record("&str", "u8", true);
+
+        assert_eq!(counters.get::<u64, u8>(), (1, 2));
+        assert_eq!(counters.get::<u8, u64>(), (0, 0));
+        assert_eq!(
+            counters.snapshot(),
+            [("&str", "u8", 1, 0), ("u64", "u8", 1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_recorded_casts() {
+        use crate::{CastPairs, CastPipeline, Match, TryCastFrom};
+
+        static COUNTERS: CastCounters = CastCounters::new();
+        set_recorder(&COUNTERS).unwrap();
+
+        #[derive(Debug, Eq, PartialEq, Hash)]
+        struct Key;
+
+        impl TryCastFrom<i8> for Key {
+            fn can_cast_from(value: &i8) -> bool {
+                *value >= 0
+            }
+
+            fn opt_cast_from(value: i8) -> Option<Self> {
+                Self::can_cast_from(&value).then_some(Self)
+            }
+        }
+
+        let map: Result<HashMap<Key, u64>, _> = [(1i8, 1u16)].into_iter().try_cast_map();
+        assert!(map.is_ok());
+
+        let map: Result<HashMap<Key, u64>, _> = [(-1i8, 1u16)].into_iter().try_cast_map();
+        assert!(map.is_err());
+        assert_eq!(COUNTERS.get::<i8, Key>(), (1, 1));
+
+        let pipeline = CastPipeline::<i8>::new().step::<Key>();
+        assert!(pipeline.try_run(-1).is_err());
+        assert_eq!(COUNTERS.get::<i8, Key>(), (1, 2));
+
+        assert!(!(-1i8).matches::<Key>());
+        assert_eq!(COUNTERS.get::<i8, Key>(), (1, 2));
+    }
+}
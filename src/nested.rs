@@ -0,0 +1,179 @@
+//! Stack-safe traversal of deeply nested values.
+//!
+//! A recursive implementation of [`TryCastFrom`](crate::TryCastFrom) for a recursive type will
+//! overflow the stack given a sufficiently deeply nested input. The helpers in this module store
+//! their work list on the heap instead, so that the depth of an input is limited only by memory.
+
+/// A recursive value whose immediate children can be enumerated.
+///
+/// Implement this trait for a recursive container type (such as a dynamic value `enum`) to
+/// check or convert arbitrarily deep instances without recursion. Example:
+/// ```
+/// use safecast::{try_fold_nested, Nested, TryCastFrom};
+///
+/// enum Value {
+///     Int(u64),
+///     List(Vec<Value>),
+/// }
+///
+/// impl Nested for Value {
+///     fn children<'a>(&'a self, stack: &mut Vec<&'a Self>) {
+///         if let Self::List(items) = self {
+///             stack.extend(items);
+///         }
+///     }
+/// }
+///
+/// enum Tree {
+///     Leaf(u32),
+///     Branch(Vec<Tree>),
+/// }
+///
+/// impl TryCastFrom<Value> for Tree {
+///     fn can_cast_from(value: &Value) -> bool {
+///         value.all_nested(|value| match value {
+///             Value::Int(i) => *i <= u32::MAX as u64,
+///             Value::List(_) => true,
+///         })
+///     }
+///
+///     fn opt_cast_from(value: Value) -> Option<Self> {
+///         try_fold_nested(
+///             value,
+///             |value| match value {
+///                 Value::Int(i) => (Some(i), vec![]),
+///                 Value::List(items) => (None, items),
+///             },
+///             |int, branches| match int {
+///                 Some(i) => u32::try_from(i).ok().map(Tree::Leaf),
+///                 None => Some(Tree::Branch(branches)),
+///             },
+///         )
+///     }
+/// }
+///
+/// let value = Value::List(vec![Value::Int(1), Value::List(vec![Value::Int(2)])]);
+/// assert!(Tree::can_cast_from(&value));
+/// ```
+pub trait Nested: Sized {
+    /// Push a reference to each immediate child of this value onto `stack`.
+    fn children<'a>(&'a self, stack: &mut Vec<&'a Self>);
+
+    /// Returns `true` if `check` returns `true` for this value and every value nested within it.
+    /// `check` should test only the value it's given, not that value's children.
+    /// Stops at the first value for which `check` returns `false`.
+    fn all_nested<F: FnMut(&Self) -> bool>(&self, mut check: F) -> bool {
+        let mut stack = vec![self];
+
+        while let Some(value) = stack.pop() {
+            if !check(value) {
+                return false;
+            }
+
+            value.children(&mut stack);
+        }
+
+        true
+    }
+}
+
+struct Frame<T, N, U> {
+    node: N,
+    children: std::vec::IntoIter<T>,
+    done: Vec<U>,
+}
+
+/// Convert `value` bottom-up, without recursion.
+///
+/// `split` separates a value into its own node data and its immediate children, and `build`
+/// combines the node data of a value with its children once they have been converted. Returns
+/// `None` as soon as any call to `build` returns `None`.
+///
+/// Because each child is moved out of its parent before being converted, this function also
+/// drops a deeply nested `value` without recursion, unless conversion stops part-way through.
+pub fn try_fold_nested<T, N, U, S, B>(value: T, mut split: S, mut build: B) -> Option<U>
+where
+    S: FnMut(T) -> (N, Vec<T>),
+    B: FnMut(N, Vec<U>) -> Option<U>,
+{
+    let frame = |(node, children): (N, Vec<T>)| Frame {
+        node,
+        done: Vec::with_capacity(children.len()),
+        children: children.into_iter(),
+    };
+
+    let mut stack = vec![frame(split(value))];
+
+    loop {
+        let top = stack.last_mut().expect("frame");
+
+        if let Some(child) = top.children.next() {
+            let child = frame(split(child));
+            stack.push(child);
+        } else {
+            let Frame { node, done, .. } = stack.pop().expect("frame");
+            let converted = build(node, done)?;
+
+            match stack.last_mut() {
+                Some(parent) => parent.done.push(converted),
+                None => return Some(converted),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEPTH: usize = 100_000;
+
+    #[allow(dead_code)]
+    enum Value {
+        Int(u64),
+        List(Vec<Value>),
+    }
+
+    impl Nested for Value {
+        fn children<'a>(&'a self, stack: &mut Vec<&'a Self>) {
+            if let Self::List(items) = self {
+                stack.extend(items);
+            }
+        }
+    }
+
+    fn split(value: Value) -> (Option<u64>, Vec<Value>) {
+        match value {
+            Value::Int(i) => (Some(i), vec![]),
+            Value::List(items) => (None, items),
+        }
+    }
+
+    #[test]
+    fn test_nested() {
+        let value = (0..DEPTH).fold(Value::Int(1), |value, _| {
+            Value::List(vec![value, Value::Int(0)])
+        });
+
+        assert!(value.all_nested(|value| !matches!(value, Value::List(items) if items.is_empty())));
+        assert!(!value.all_nested(|value| !matches!(value, Value::Int(1))));
+
+        let depth = try_fold_nested(value, split, |int, depths: Vec<usize>| match int {
+            Some(_) => Some(0),
+            None => depths.into_iter().max().map(|depth| depth + 1),
+        });
+
+        assert_eq!(depth, Some(DEPTH));
+    }
+
+    #[test]
+    fn test_nested_failure() {
+        let value = (0..DEPTH).fold(Value::Int(1), |value, _| Value::List(vec![value]));
+        let sum = try_fold_nested(value, split, |int, sums: Vec<u64>| match int {
+            Some(_) => None,
+            None => Some(sums.into_iter().sum::<u64>()),
+        });
+
+        assert_eq!(sum, None);
+    }
+}
@@ -0,0 +1,80 @@
+//! A [`Flatten`] wrapper to cast nested [`Option`]s into a single level of optionality.
+
+use std::ops::Deref;
+
+use super::TryCastFrom;
+
+/// An optional value cast from an `Option<Option<T>>`, where both `None` and `Some(None)`
+/// become `None`, and `Some(Some(t))` becomes `Some(t)` cast into `T`.
+///
+/// Casting `Option<Option<T>>` directly into `Option<T>` would overlap with the blanket
+/// implementation of [`TryCastFrom`] for types which implement [`From`], so this explicit
+/// wrapper is used instead. Example:
+/// ```
+/// use safecast::{Flatten, TryCastFrom, TryCastInto};
+///
+/// let nested: Option<Option<u8>> = Some(Some(1));
+/// let flat = Flatten::<u64>::opt_cast_from(nested).unwrap();
+/// assert_eq!(flat.into_inner(), Some(1));
+///
+/// let flat: Option<Flatten<u64>> = Some(None::<u8>).opt_cast_into();
+/// assert_eq!(flat, Some(Flatten(None)));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Flatten<T>(pub Option<T>);
+
+impl<T> Flatten<T> {
+    /// Return the flattened value.
+    pub fn into_inner(self) -> Option<T> {
+        self.0
+    }
+}
+
+impl<T> Deref for Flatten<T> {
+    type Target = Option<T>;
+
+    fn deref(&self) -> &Option<T> {
+        &self.0
+    }
+}
+
+impl<T> From<Flatten<T>> for Option<T> {
+    fn from(flat: Flatten<T>) -> Self {
+        flat.0
+    }
+}
+
+impl<T, U: TryCastFrom<T>> TryCastFrom<Option<Option<T>>> for Flatten<U> {
+    fn can_cast_from(value: &Option<Option<T>>) -> bool {
+        match value {
+            Some(Some(value)) => U::can_cast_from(value),
+            _ => true,
+        }
+    }
+
+    fn opt_cast_from(value: Option<Option<T>>) -> Option<Self> {
+        match value.flatten() {
+            Some(value) => U::opt_cast_from(value).map(Some).map(Self),
+            None => Some(Self(None)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Match, NonEmpty, Validated};
+
+    #[test]
+    fn test_flatten() {
+        type Name = Validated<String, NonEmpty>;
+
+        assert!(None::<Option<String>>.matches::<Flatten<Name>>());
+        assert!(Some(None::<String>).matches::<Flatten<Name>>());
+        assert!(Some(Some("a".to_string())).matches::<Flatten<Name>>());
+        assert!(!Some(Some(String::new())).matches::<Flatten<Name>>());
+
+        let flat = Flatten::<Name>::opt_cast_from(Some(Some("a".to_string()))).unwrap();
+        assert_eq!(flat.as_deref().map(String::as_str), Some("a"));
+    }
+}
@@ -0,0 +1,279 @@
+//! A [`Parsed`] wrapper to cast strings into any type which implements [`FromStr`], and
+//! [`Radix`] and [`AutoRadix`] wrappers to cast strings into integers in other bases.
+
+use std::num::ParseIntError;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use super::TryCastFrom;
+
+/// A value cast from a string using its [`FromStr`] implementation.
+///
+/// Casting a string directly into a type which implements [`FromStr`] would overlap with the
+/// blanket implementation of [`TryCastFrom`] for types which implement [`From`], so this
+/// explicit wrapper is used instead. Note that `can_cast_from` has to parse the string in
+/// order to validate it, so prefer `opt_cast_from` when the parsed value is needed.
+/// Example:
+/// ```
+/// use std::net::IpAddr;
+/// use safecast::{Parsed, TryCastFrom};
+///
+/// assert!(Parsed::<IpAddr>::can_cast_from(&"127.0.0.1"));
+/// assert!(Parsed::<IpAddr>::opt_cast_from("localhost").is_none());
+///
+/// let port = Parsed::<u16>::opt_cast_from("8080".to_string()).unwrap();
+/// assert_eq!(port.into_inner(), 8080);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Parsed<T>(pub T);
+
+impl<T> Parsed<T> {
+    /// Return the parsed value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Parsed<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<'a, T: FromStr> TryCastFrom<&'a str> for Parsed<T> {
+    fn can_cast_from(value: &&'a str) -> bool {
+        value.parse::<T>().is_ok()
+    }
+
+    fn opt_cast_from(value: &'a str) -> Option<Self> {
+        value.parse().ok().map(Self)
+    }
+}
+
+impl<T: FromStr> TryCastFrom<String> for Parsed<T> {
+    fn can_cast_from(value: &String) -> bool {
+        value.parse::<T>().is_ok()
+    }
+
+    fn opt_cast_from(value: String) -> Option<Self> {
+        value.parse().ok().map(Self)
+    }
+}
+
+/// An integer type which can be parsed from a string in a given radix.
+pub trait FromStrRadix: Sized {
+    /// Parse an integer from a string of digits in the given `radix`, with an optional sign.
+    fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError>;
+}
+
+macro_rules! from_str_radix {
+    ($($t:ty),*) => {
+        $(
+            impl FromStrRadix for $t {
+                fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError> {
+                    <$t>::from_str_radix(src, radix)
+                }
+            }
+        )*
+    };
+}
+
+from_str_radix!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// An integer cast from a string of digits in the radix `R`, which must be between 2 and 36.
+///
+/// For a radix of 16, 8, or 2, the digits may be preceded by the prefix `0x`, `0o`, or `0b`
+/// (in either case), and in any radix, by a sign. Example:
+/// ```
+/// use safecast::{Binary, Hex, Radix, TryCastFrom};
+///
+/// assert_eq!(Hex::<u64>::opt_cast_from("0xFF"), Some(Radix(255)));
+/// assert_eq!(Hex::<u64>::opt_cast_from("ff"), Some(Radix(255)));
+/// assert_eq!(Hex::<i32>::opt_cast_from("-0x10"), Some(Radix(-16)));
+/// assert_eq!(Binary::<u8>::opt_cast_from("0b101".to_string()), Some(Radix(5)));
+/// assert_eq!(Radix::<u16, 36>::opt_cast_from("zz"), Some(Radix(1295)));
+///
+/// assert!(!Hex::<u8>::can_cast_from(&"0x100"));
+/// assert!(!Binary::<u8>::can_cast_from(&"0x1"));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Radix<T, const R: u32>(pub T);
+
+/// An integer cast from a string of hexadecimal digits. See [`Radix`].
+pub type Hex<T> = Radix<T, 16>;
+
+/// An integer cast from a string of octal digits. See [`Radix`].
+pub type Octal<T> = Radix<T, 8>;
+
+/// An integer cast from a string of binary digits. See [`Radix`].
+pub type Binary<T> = Radix<T, 2>;
+
+impl<T, const R: u32> Radix<T, R> {
+    const VALID: () = assert!(R >= 2 && R <= 36, "a radix must be between 2 and 36");
+
+    /// Return the parsed integer.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, const R: u32> Deref for Radix<T, R> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// An integer cast from a string whose prefix determines its radix: `0x` for hexadecimal, `0o`
+/// for octal, `0b` for binary (in either case), or else decimal, optionally preceded by a sign.
+///
+/// This is the format of an integer literal in Rust (without underscores), which is a common
+/// format for configuration values. Example:
+/// ```
+/// use safecast::{AutoRadix, TryCastFrom};
+///
+/// assert_eq!(AutoRadix::<u32>::opt_cast_from("0x1F"), Some(AutoRadix(31)));
+/// assert_eq!(AutoRadix::<u32>::opt_cast_from("0o17"), Some(AutoRadix(15)));
+/// assert_eq!(AutoRadix::<u32>::opt_cast_from("31"), Some(AutoRadix(31)));
+/// assert_eq!(AutoRadix::<i8>::opt_cast_from("-0b1"), Some(AutoRadix(-1)));
+/// assert!(AutoRadix::<u32>::opt_cast_from("1F").is_none());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct AutoRadix<T>(pub T);
+
+impl<T> AutoRadix<T> {
+    /// Return the parsed integer.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for AutoRadix<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Split `value` into its sign (if negative) and the rest.
+fn split_sign(value: &str) -> (bool, &str) {
+    match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    }
+}
+
+/// Split the radix prefix (if any) from `value`.
+fn split_prefix(value: &str) -> (Option<u32>, &str) {
+    let radix = match value.get(..2) {
+        Some("0x" | "0X") => 16,
+        Some("0o" | "0O") => 8,
+        Some("0b" | "0B") => 2,
+        _ => return (None, value),
+    };
+
+    (Some(radix), &value[2..])
+}
+
+fn parse_digits<T: FromStrRadix>(negative: bool, digits: &str, radix: u32) -> Option<T> {
+    if digits.starts_with(['+', '-']) {
+        None
+    } else if negative {
+        T::from_str_radix(&format!("-{}", digits), radix).ok()
+    } else {
+        T::from_str_radix(digits, radix).ok()
+    }
+}
+
+fn parse_radix<T: FromStrRadix>(value: &str, radix: u32) -> Option<T> {
+    let (negative, rest) = split_sign(value);
+
+    let digits = match split_prefix(rest) {
+        (Some(prefix), digits) if prefix == radix => digits,
+        _ => rest,
+    };
+
+    parse_digits(negative, digits, radix)
+}
+
+fn parse_auto_radix<T: FromStrRadix>(value: &str) -> Option<T> {
+    let (negative, rest) = split_sign(value);
+    let (radix, digits) = split_prefix(rest);
+    parse_digits(negative, digits, radix.unwrap_or(10))
+}
+
+macro_rules! radix {
+    ($($source:ty; $($lt:lifetime)?),*) => {
+        $(
+            impl<$($lt,)? T: FromStrRadix, const R: u32> TryCastFrom<$source> for Radix<T, R> {
+                fn can_cast_from(value: &$source) -> bool {
+                    Self::opt_cast_from_str(value).is_some()
+                }
+
+                fn opt_cast_from(value: $source) -> Option<Self> {
+                    Self::opt_cast_from_str(&value)
+                }
+            }
+
+            impl<$($lt,)? T: FromStrRadix> TryCastFrom<$source> for AutoRadix<T> {
+                fn can_cast_from(value: &$source) -> bool {
+                    parse_auto_radix::<T>(value).is_some()
+                }
+
+                fn opt_cast_from(value: $source) -> Option<Self> {
+                    parse_auto_radix(&value).map(Self)
+                }
+            }
+        )*
+    };
+}
+
+impl<T: FromStrRadix, const R: u32> Radix<T, R> {
+    fn opt_cast_from_str(value: &str) -> Option<Self> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID;
+        parse_radix(value, R).map(Self)
+    }
+}
+
+radix!(&'a str; 'a, String;);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Match;
+
+    #[test]
+    fn test_parsed() {
+        assert!("1.5".to_string().matches::<Parsed<f32>>());
+        assert!(!"1.5".to_string().matches::<Parsed<u8>>());
+        assert_eq!(Parsed::<bool>::opt_cast_from("true"), Some(Parsed(true)));
+    }
+
+    #[test]
+    fn test_radix() {
+        assert_eq!(Hex::<u16>::opt_cast_from("0XbEeF"), Some(Radix(0xbeef)));
+        assert_eq!(Octal::<u8>::opt_cast_from("+0o777"), None);
+        assert_eq!(Octal::<u16>::opt_cast_from("+0o777"), Some(Radix(0o777)));
+        assert_eq!(Hex::<u8>::opt_cast_from("0b1"), Some(Radix(0xb1)));
+        assert!(!Hex::<i8>::can_cast_from(&"0x-1"));
+        assert!(!Hex::<u8>::can_cast_from(&"0x"));
+        assert!(!"-1".to_string().matches::<Hex<u8>>());
+    }
+
+    #[test]
+    fn test_auto_radix() {
+        assert_eq!(
+            AutoRadix::<u64>::opt_cast_from("0xff"),
+            Some(AutoRadix(255))
+        );
+        assert_eq!(AutoRadix::<u64>::opt_cast_from("0B11"), Some(AutoRadix(3)));
+        assert_eq!(AutoRadix::<i64>::opt_cast_from("-10"), Some(AutoRadix(-10)));
+        assert!(!AutoRadix::<u8>::can_cast_from(&"0o8"));
+        assert!(!"--1".to_string().matches::<AutoRadix<i8>>());
+    }
+}
@@ -0,0 +1,247 @@
+//! A [`CastPipeline`] which composes a sequence of fallible casts.
+
+use std::any::type_name;
+use std::convert::Infallible;
+use std::fmt;
+
+use super::{CastError, Casted, TryCastFrom};
+
+type Run<S, T> = Box<dyn Fn(S) -> Result<T, (usize, CastError)> + Send + Sync>;
+
+/// A sequence of fallible casts from `S` into `T` which records which step failed.
+/// Example:
+/// ```
+/// use safecast::{CastPipeline, TryCastFrom};
+///
+/// struct Celsius(f64);
+///
+/// #[derive(Debug)]
+/// struct Kelvin(f64);
+///
+/// impl TryCastFrom<f64> for Celsius {
+///     fn can_cast_from(value: &f64) -> bool {
+///         value.is_finite()
+///     }
+///
+///     fn opt_cast_from(value: f64) -> Option<Self> {
+///         if value.is_finite() {
+///             Some(Self(value))
+///         } else {
+///             None
+///         }
+///     }
+/// }
+///
+/// impl TryCastFrom<Celsius> for Kelvin {
+///     fn can_cast_from(value: &Celsius) -> bool {
+///         value.0 >= -273.15
+///     }
+///
+///     fn opt_cast_from(value: Celsius) -> Option<Self> {
+///         if value.0 >= -273.15 {
+///             Some(Self(value.0 + 273.15))
+///         } else {
+///             None
+///         }
+///     }
+/// }
+///
+/// let pipeline = CastPipeline::<f64>::new()
+///     .step::<Celsius>()
+///     .context("read temperature")
+///     .step::<Kelvin>()
+///     .context("convert to kelvin");
+///
+/// assert!(pipeline.try_run(20.).is_ok());
+///
+/// let err = pipeline.try_run(-300.).unwrap_err();
+/// assert_eq!(err.step(), 1);
+/// assert_eq!(err.context(), ["read temperature", "convert to kelvin"]);
+/// ```
+pub struct CastPipeline<S, T = S> {
+    run: Run<S, T>,
+    context: Vec<Option<String>>,
+    hops: Vec<&'static str>,
+}
+
+impl<S: 'static> CastPipeline<S, S> {
+    /// Construct a new, empty pipeline.
+    pub fn new() -> Self {
+        Self {
+            run: Box::new(Ok),
+            context: Vec::new(),
+            hops: Vec::new(),
+        }
+    }
+}
+
+impl<S: 'static> Default for CastPipeline<S, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: 'static, T: 'static> CastPipeline<S, T> {
+    /// Append a step which casts the output of this pipeline into `U`.
+    pub fn step<U: TryCastFrom<T> + 'static>(self) -> CastPipeline<S, U> {
+        let run = self.run;
+        let step = self.context.len();
+
+        let mut context = self.context;
+        context.push(None);
+
+        let mut hops = self.hops;
+        hops.push(type_name::<T>());
+
+        CastPipeline {
+            run: Box::new(move |value| {
+                let value = run(value)?;
+                U::try_cast_from(value, |_| (step, CastError::new::<T, U>()))
+            }),
+            context,
+            hops,
+        }
+    }
+
+    /// Describe the most recently added step, for inclusion in the [`PipelineError`].
+    ///
+    /// Context can only be attached to a step, so this must be called after [`Self::step`];
+    /// with `debug_assertions` enabled, calling it on an empty pipeline panics.
+    pub fn context<C: fmt::Display>(mut self, context: C) -> Self {
+        debug_assert!(
+            !self.is_empty(),
+            "CastPipeline::context describes the most recent step, but there are no steps"
+        );
+
+        if let Some(last) = self.context.last_mut() {
+            *last = Some(context.to_string());
+        }
+
+        self
+    }
+
+    /// The number of steps in this pipeline.
+    pub fn len(&self) -> usize {
+        self.context.len()
+    }
+
+    /// Returns `true` if this pipeline has no steps.
+    pub fn is_empty(&self) -> bool {
+        self.context.is_empty()
+    }
+
+    /// Cast `value` through each step of this pipeline in order.
+    pub fn try_run(&self, value: S) -> Result<T, PipelineError> {
+        (self.run)(value).map_err(|(step, error)| PipelineError {
+            step,
+            error,
+            context: self.context[..=step].iter().flatten().cloned().collect(),
+        })
+    }
+
+    /// Cast `value` through each step of this pipeline in order, recording the type of each
+    /// intermediate value in the result.
+    pub fn try_run_casted(&self, value: S) -> Result<Casted<T>, PipelineError> {
+        self.try_run(value)
+            .map(|value| Casted::new(value, self.hops.clone()))
+    }
+}
+
+/// An error which occurs when a step of a [`CastPipeline`] fails.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PipelineError {
+    step: usize,
+    error: CastError,
+    context: Vec<String>,
+}
+
+impl PipelineError {
+    /// The index of the step which failed.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// The [`CastError`] produced by the failed step.
+    pub fn error(&self) -> &CastError {
+        &self.error
+    }
+
+    /// The context of each step up to and including the failed step, in order.
+    pub fn context(&self) -> &[String] {
+        &self.context
+    }
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cast pipeline failed at step {}", self.step)?;
+
+        for context in &self.context {
+            write!(f, ": {}", context)?;
+        }
+
+        write!(f, ": {}", self.error)
+    }
+}
+
+impl std::error::Error for PipelineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<Infallible> for PipelineError {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NonEmpty, Parsed, Validated};
+
+    #[test]
+    fn test_pipeline() {
+        let pipeline = CastPipeline::<&'static str>::new()
+            .step::<Validated<&'static str, NonEmpty>>()
+            .context("require a name")
+            .step::<Validated<&'static str, NonEmpty>>();
+
+        assert_eq!(pipeline.len(), 2);
+        assert_eq!(pipeline.try_run("name").unwrap().into_inner(), "name");
+
+        let err = pipeline.try_run("").unwrap_err();
+        assert_eq!(err.step(), 0);
+        assert_eq!(err.context(), ["require a name"]);
+        assert_eq!(
+            err.error(),
+            &CastError::new::<&str, Validated<&str, NonEmpty>>()
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "there are no steps")]
+    fn test_pipeline_context_without_steps() {
+        let _ = CastPipeline::<u8>::new().context("unused");
+    }
+
+    #[test]
+    fn test_pipeline_casted() {
+        let pipeline = CastPipeline::<&'static str>::new()
+            .step::<String>()
+            .step::<Parsed<u8>>();
+
+        let value = pipeline.try_run_casted("1").unwrap();
+        assert_eq!(value.hops(), [type_name::<&str>(), type_name::<String>()]);
+        assert_eq!(value.into_inner(), Parsed(1));
+
+        assert!(pipeline.try_run_casted("256").is_err());
+        assert!(CastPipeline::<u8>::new()
+            .try_run_casted(1)
+            .unwrap()
+            .hops()
+            .is_empty());
+    }
+}
@@ -0,0 +1,348 @@
+//! A thread-safe registry of cast rules which can be extended at runtime, for example by
+//! dynamically loaded plugins.
+//!
+//! Rules are grouped into namespaces, so that each plugin can contribute (and later remove) its
+//! own set of converters without interfering with any other. A [`CastRegistry`] is `Send` and
+//! `Sync`; the converters themselves are called without holding the registry lock, so a slow
+//! or panicking converter never blocks or poisons concurrent registration or lookup.
+//!
+//! Example:
+//! ```
+//! use safecast::registry::{CastRegistry, RegistryError};
+//!
+//! let registry = CastRegistry::new();
+//! registry.register::<u8, u64>("core").unwrap();
+//! registry
+//!     .register_with("plugin", |s: String| s.parse::<u64>().ok())
+//!     .unwrap();
+//!
+//! assert_eq!(registry.cast::<u8, u64>("core", 1), Ok(1));
+//! assert_eq!(registry.cast::<String, u64>("plugin", "2".into()), Ok(2));
+//!
+//! assert!(matches!(
+//!     registry.cast::<String, u64>("core", "2".into()),
+//!     Err(RegistryError::NotFound(_))
+//! ));
+//!
+//! assert_eq!(registry.remove_namespace("plugin"), 1);
+//! assert!(!registry.contains::<String, u64>("plugin"));
+//! ```
+
+use std::any::{type_name, Any, TypeId};
+use std::collections::hash_map::{Entry, HashMap};
+use std::convert::Infallible;
+use std::fmt;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use super::{CastError, TryCastFrom};
+
+type Converter = Arc<dyn Fn(Box<dyn Any + Send>) -> Option<Box<dyn Any + Send>> + Send + Sync>;
+
+type Namespace = HashMap<(TypeId, TypeId), (Rule, Converter)>;
+
+/// A description of a cast rule from one type into another within a namespace.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rule {
+    namespace: String,
+    source_type: &'static str,
+    target_type: &'static str,
+}
+
+impl Rule {
+    fn new<F, T>(namespace: &str) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            source_type: type_name::<F>(),
+            target_type: type_name::<T>(),
+        }
+    }
+
+    /// The namespace of this rule.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// The name of the type which this rule casts from.
+    pub fn source_type(&self) -> &'static str {
+        self.source_type
+    }
+
+    /// The name of the type which this rule casts into.
+    pub fn target_type(&self) -> &'static str {
+        self.target_type
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} into {} in namespace {}",
+            self.source_type, self.target_type, self.namespace
+        )
+    }
+}
+
+/// An error which occurs when registering, removing, or applying a cast rule.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RegistryError {
+    /// A rule for the same source and target types is already registered in the namespace.
+    Duplicate(Rule),
+    /// No rule for the given source and target types is registered in the namespace.
+    NotFound(Rule),
+    /// A rule was found, but the value could not be cast.
+    Cast(CastError),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Duplicate(rule) => write!(f, "duplicate cast rule: {}", rule),
+            Self::NotFound(rule) => write!(f, "no cast rule registered: {}", rule),
+            Self::Cast(cause) => fmt::Display::fmt(cause, f),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Cast(cause) => Some(cause),
+            _ => None,
+        }
+    }
+}
+
+impl From<Infallible> for RegistryError {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+impl From<CastError> for RegistryError {
+    fn from(cause: CastError) -> Self {
+        Self::Cast(cause)
+    }
+}
+
+/// A thread-safe set of namespaced cast rules which can be modified at runtime.
+#[derive(Default)]
+pub struct CastRegistry {
+    namespaces: RwLock<HashMap<String, Namespace>>,
+}
+
+impl CastRegistry {
+    /// Construct a new, empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Borrow the process-wide global registry.
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<CastRegistry> = OnceLock::new();
+        GLOBAL.get_or_init(Self::new)
+    }
+
+    /// Register the [`TryCastFrom`] implementation of `T` as the rule to cast `F` into `T`
+    /// in the given `namespace`.
+    pub fn register<F, T>(&self, namespace: &str) -> Result<(), RegistryError>
+    where
+        F: Send + 'static,
+        T: TryCastFrom<F> + Send + 'static,
+    {
+        self.register_with(namespace, T::opt_cast_from)
+    }
+
+    /// Register `convert` as the rule to cast `F` into `T` in the given `namespace`.
+    pub fn register_with<F, T, C>(&self, namespace: &str, convert: C) -> Result<(), RegistryError>
+    where
+        F: Send + 'static,
+        T: Send + 'static,
+        C: Fn(F) -> Option<T> + Send + Sync + 'static,
+    {
+        let rule = Rule::new::<F, T>(namespace);
+
+        let converter: Converter = Arc::new(move |value: Box<dyn Any + Send>| {
+            let value = value.downcast::<F>().expect("cast rule source type");
+            convert(*value).map(|value| Box::new(value) as Box<dyn Any + Send>)
+        });
+
+        let mut namespaces = self.write();
+        let rules = namespaces.entry(namespace.to_string()).or_default();
+
+        match rules.entry((TypeId::of::<F>(), TypeId::of::<T>())) {
+            Entry::Occupied(_) => Err(RegistryError::Duplicate(rule)),
+            Entry::Vacant(entry) => {
+                entry.insert((rule, converter));
+                Ok(())
+            }
+        }
+    }
+
+    /// Remove the rule to cast `F` into `T` from the given `namespace`.
+    pub fn unregister<F: 'static, T: 'static>(&self, namespace: &str) -> Result<(), RegistryError> {
+        let mut namespaces = self.write();
+        let key = (TypeId::of::<F>(), TypeId::of::<T>());
+
+        let removed = namespaces
+            .get_mut(namespace)
+            .and_then(|rules| rules.remove(&key));
+
+        if namespaces.get(namespace).is_some_and(HashMap::is_empty) {
+            namespaces.remove(namespace);
+        }
+
+        removed
+            .map(|_| ())
+            .ok_or_else(|| RegistryError::NotFound(Rule::new::<F, T>(namespace)))
+    }
+
+    /// Remove every rule in the given `namespace`, returning the number of rules removed.
+    pub fn remove_namespace(&self, namespace: &str) -> usize {
+        let mut namespaces = self.write();
+        namespaces.remove(namespace).map_or(0, |rules| rules.len())
+    }
+
+    /// Returns `true` if a rule to cast `F` into `T` is registered in the given `namespace`.
+    pub fn contains<F: 'static, T: 'static>(&self, namespace: &str) -> bool {
+        let namespaces = self.read();
+        let key = (TypeId::of::<F>(), TypeId::of::<T>());
+
+        namespaces
+            .get(namespace)
+            .is_some_and(|rules| rules.contains_key(&key))
+    }
+
+    /// List the rules registered in the given `namespace`.
+    pub fn rules(&self, namespace: &str) -> Vec<Rule> {
+        let namespaces = self.read();
+
+        namespaces
+            .get(namespace)
+            .map(|rules| rules.values().map(|(rule, _)| rule.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// List the namespaces which contain at least one rule.
+    pub fn namespaces(&self) -> Vec<String> {
+        let namespaces = self.read();
+        namespaces.keys().cloned().collect()
+    }
+
+    /// Cast `value` into `T` using the rule registered in the given `namespace`.
+    pub fn cast<F, T>(&self, namespace: &str, value: F) -> Result<T, RegistryError>
+    where
+        F: Send + 'static,
+        T: Send + 'static,
+    {
+        let converter = {
+            let namespaces = self.read();
+            let key = (TypeId::of::<F>(), TypeId::of::<T>());
+
+            namespaces
+                .get(namespace)
+                .and_then(|rules| rules.get(&key))
+                .map(|(_, converter)| converter.clone())
+                .ok_or_else(|| RegistryError::NotFound(Rule::new::<F, T>(namespace)))?
+        };
+
+        let value = converter(Box::new(value)).ok_or_else(CastError::new::<F, T>)?;
+        Ok(*value.downcast::<T>().expect("cast rule target type"))
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<String, Namespace>> {
+        self.namespaces
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, Namespace>> {
+        self.namespaces
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for CastRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let namespaces = self.read();
+
+        f.debug_map()
+            .entries(namespaces.iter().map(|(namespace, rules)| {
+                let rules: Vec<&Rule> = rules.values().map(|(rule, _)| rule).collect();
+                (namespace, rules)
+            }))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::Parsed;
+
+    #[test]
+    fn test_registry() {
+        let registry = CastRegistry::new();
+        registry.register::<&'static str, Parsed<u8>>("a").unwrap();
+
+        assert_eq!(
+            registry.register::<&'static str, Parsed<u8>>("a"),
+            Err(RegistryError::Duplicate(Rule::new::<&str, Parsed<u8>>("a")))
+        );
+
+        assert_eq!(registry.cast::<_, Parsed<u8>>("a", "1"), Ok(Parsed(1)));
+        assert_eq!(
+            registry.cast::<_, Parsed<u8>>("a", "256"),
+            Err(RegistryError::Cast(CastError::new::<&str, Parsed<u8>>()))
+        );
+
+        registry
+            .unregister::<&'static str, Parsed<u8>>("a")
+            .unwrap();
+        assert!(registry.namespaces().is_empty());
+        assert!(registry
+            .unregister::<&'static str, Parsed<u8>>("a")
+            .is_err());
+    }
+
+    #[test]
+    fn test_registry_namespaces() {
+        let registry = CastRegistry::new();
+        registry.register::<u8, u64>("a").unwrap();
+        registry.register::<u8, u64>("b").unwrap();
+        registry.register::<u16, u64>("b").unwrap();
+
+        assert_eq!(registry.rules("a"), [Rule::new::<u8, u64>("a")]);
+        assert_eq!(registry.remove_namespace("b"), 2);
+        assert!(registry.contains::<u8, u64>("a"));
+        assert!(!registry.contains::<u8, u64>("b"));
+    }
+
+    #[test]
+    fn test_registry_concurrent() {
+        let registry = Arc::new(CastRegistry::new());
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let registry = registry.clone();
+                thread::spawn(move || {
+                    let namespace = format!("plugin{}", i);
+                    registry
+                        .register_with(&namespace, move |n: u64| n.checked_add(i))
+                        .unwrap();
+
+                    registry.cast::<u64, u64>(&namespace, 1).unwrap()
+                })
+            })
+            .collect();
+
+        let mut results: Vec<u64> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        results.sort();
+
+        assert_eq!(results, (1..9).collect::<Vec<u64>>());
+        assert_eq!(registry.namespaces().len(), 8);
+    }
+}
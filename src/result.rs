@@ -0,0 +1,112 @@
+//! [`OkType`] and [`ErrType`] wrappers to access a [`Result`] through [`AsType`].
+
+use std::ops::Deref;
+
+use super::AsType;
+
+/// A [`Result`] which implements [`AsType<T>`](AsType) for its success type `T`.
+///
+/// `Result<T, E>` can't implement `AsType<T>` itself, since it doesn't implement `From<T>`, and
+/// it can't implement `AsType<T>` and `AsType<E>` at once, since these would overlap when `T` and
+/// `E` are the same type. So the two views are provided by two explicit wrappers: `OkType` for the
+/// success payload and [`ErrType`] for the error payload.
+/// Example:
+/// ```
+/// use safecast::{AsType, OkType};
+///
+/// fn payload<C: AsType<u64>>(container: &C) -> Option<&u64> {
+///     container.as_type()
+/// }
+///
+/// let ok = OkType::<u64, String>(Ok(1));
+/// assert_eq!(payload(&ok), Some(&1));
+///
+/// let err = OkType::<u64, String>(Err("oops".to_string()));
+/// assert_eq!(payload(&err), None);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct OkType<T, E>(pub Result<T, E>);
+
+/// A [`Result`] which implements [`AsType<E>`](AsType) for its error type `E`.
+/// See [`OkType`] for details.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ErrType<T, E>(pub Result<T, E>);
+
+macro_rules! result_type {
+    ($wrapper:ident, $variant:ident, $t:ident) => {
+        impl<T, E> $wrapper<T, E> {
+            /// Return the wrapped [`Result`].
+            pub fn into_inner(self) -> Result<T, E> {
+                self.0
+            }
+        }
+
+        impl<T, E> Deref for $wrapper<T, E> {
+            type Target = Result<T, E>;
+
+            fn deref(&self) -> &Result<T, E> {
+                &self.0
+            }
+        }
+
+        impl<T, E> From<$wrapper<T, E>> for Result<T, E> {
+            fn from(result: $wrapper<T, E>) -> Self {
+                result.0
+            }
+        }
+
+        impl<T, E> From<$t> for $wrapper<T, E> {
+            fn from(value: $t) -> Self {
+                Self($variant(value))
+            }
+        }
+
+        impl<T, E> AsType<$t> for $wrapper<T, E> {
+            fn as_type(&self) -> Option<&$t> {
+                match &self.0 {
+                    $variant(value) => Some(value),
+                    _ => None,
+                }
+            }
+
+            fn as_type_mut(&mut self) -> Option<&mut $t> {
+                match &mut self.0 {
+                    $variant(value) => Some(value),
+                    _ => None,
+                }
+            }
+
+            fn into_type(self) -> Option<$t> {
+                match self.0 {
+                    $variant(value) => Some(value),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+result_type!(OkType, Ok, T);
+result_type!(ErrType, Err, E);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ok_type() {
+        let mut result = OkType::<u8, u8>(Err(1));
+        assert_eq!(result.as_type(), None);
+
+        *result.get_or_insert_type(2) += 1;
+        assert_eq!(result.into_inner(), Ok(3));
+    }
+
+    #[test]
+    fn test_err_type() {
+        let result = ErrType::<u8, &str>::from("oops");
+        assert_eq!(result.as_type(), Some(&"oops"));
+        assert!(result.is_err());
+        assert_eq!(ErrType::<u8, &str>(Ok(1)).into_type(), None);
+    }
+}
@@ -0,0 +1,148 @@
+//! Reflection on the types which a container type can be cast into, for diagnostics.
+
+/// A container type (such as an `enum`) which can enumerate the types it can be cast into.
+///
+/// Use the [`cast_targets!`](crate::cast_targets) macro to implement this trait.
+pub trait CastTargets {
+    /// The names of every type which an instance of `Self` may be cast into.
+    fn cast_targets() -> &'static [&'static str];
+
+    /// The names of the types which this instance can be cast into.
+    fn matching_targets(&self) -> Vec<&'static str>;
+
+    /// Describe the types which an instance of `Self` may be cast into, for an error message.
+    fn describe_targets() -> String {
+        match Self::cast_targets() {
+            [] => "nothing".to_string(),
+            [target] => target.to_string(),
+            targets => format!("one of: {}", targets.join(", ")),
+        }
+    }
+}
+
+/// Implement [`CastTargets`](crate::CastTargets) for a container type, given a list of the
+/// types it may be cast into. Each target type must implement
+/// [`TryCastFrom`](crate::TryCastFrom) for the container type. Example:
+/// ```
+/// use safecast::{cast_targets, CastTargets, TryCastFrom};
+///
+/// enum Value {
+///     Int(i64),
+///     String(String),
+/// }
+///
+/// impl TryCastFrom<Value> for u64 {
+///     fn can_cast_from(value: &Value) -> bool {
+///         matches!(value, Value::Int(i) if *i >= 0)
+///     }
+///
+///     fn opt_cast_from(value: Value) -> Option<Self> {
+///         match value {
+///             Value::Int(i) => i.try_into().ok(),
+///             Value::String(_) => None,
+///         }
+///     }
+/// }
+///
+/// impl TryCastFrom<Value> for String {
+///     fn can_cast_from(value: &Value) -> bool {
+///         matches!(value, Value::String(_))
+///     }
+///
+///     fn opt_cast_from(value: Value) -> Option<Self> {
+///         match value {
+///             Value::String(s) => Some(s),
+///             Value::Int(_) => None,
+///         }
+///     }
+/// }
+///
+/// cast_targets!(Value, [u64, String]);
+///
+/// assert_eq!(Value::Int(1).matching_targets(), ["u64"]);
+/// assert!(Value::Int(-1).matching_targets().is_empty());
+/// assert_eq!(Value::describe_targets(), "one of: u64, String");
+/// ```
+#[macro_export]
+macro_rules! cast_targets {
+    ($c:ident<$($cg:tt),*>, [$($t:ty),* $(,)?]) => {
+        impl<$($cg),+> $crate::CastTargets for $c<$($cg),+> {
+            fn cast_targets() -> &'static [&'static str] {
+                &[$(stringify!($t)),*]
+            }
+
+            fn matching_targets(&self) -> Vec<&'static str> {
+                let mut targets = Vec::new();
+                $(
+                    if <$t as $crate::TryCastFrom<Self>>::can_cast_from(self) {
+                        targets.push(stringify!($t));
+                    }
+                )*
+                targets
+            }
+        }
+    };
+    ($c:ty, [$($t:ty),* $(,)?]) => {
+        impl $crate::CastTargets for $c {
+            fn cast_targets() -> &'static [&'static str] {
+                &[$(stringify!($t)),*]
+            }
+
+            fn matching_targets(&self) -> Vec<&'static str> {
+                let mut targets = Vec::new();
+                $(
+                    if <$t as $crate::TryCastFrom<Self>>::can_cast_from(self) {
+                        targets.push(stringify!($t));
+                    }
+                )*
+                targets
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TryCastFrom;
+
+    enum Value<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    impl<T> TryCastFrom<Value<T>> for Vec<T> {
+        fn can_cast_from(_: &Value<T>) -> bool {
+            true
+        }
+
+        fn opt_cast_from(value: Value<T>) -> Option<Self> {
+            match value {
+                Value::One(item) => Some(vec![item]),
+                Value::Many(items) => Some(items),
+            }
+        }
+    }
+
+    impl<T> TryCastFrom<Value<T>> for (T,) {
+        fn can_cast_from(value: &Value<T>) -> bool {
+            matches!(value, Value::One(_))
+        }
+
+        fn opt_cast_from(value: Value<T>) -> Option<Self> {
+            match value {
+                Value::One(item) => Some((item,)),
+                Value::Many(_) => None,
+            }
+        }
+    }
+
+    cast_targets!(Value<T>, [Vec<T>, (T,)]);
+
+    #[test]
+    fn test_cast_targets() {
+        assert_eq!(Value::<u8>::cast_targets(), ["Vec<T>", "(T,)"]);
+        assert_eq!(Value::One(1).matching_targets(), ["Vec<T>", "(T,)"]);
+        assert_eq!(Value::Many(vec![1]).matching_targets(), ["Vec<T>"]);
+    }
+}
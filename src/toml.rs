@@ -0,0 +1,145 @@
+//! Casting from TOML documents, using [`toml`](::toml).
+
+use ::toml::Value;
+
+use super::document::cast_parsed;
+use super::{Document, FormatCastError, ParseError, TryCastFrom};
+
+/// An error which occurs when a TOML document cannot be cast into a target type.
+pub type TomlCastError = FormatCastError<::toml::de::Error>;
+
+impl ParseError for ::toml::de::Error {
+    const FORMAT: &'static str = "TOML";
+}
+
+impl Document for Value {
+    fn as_bool(&self) -> Option<bool> {
+        Value::as_bool(self)
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        self.as_integer()
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        self.as_integer().and_then(|n| u64::try_from(n).ok())
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        self.as_float()
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        Value::as_str(self)
+    }
+
+    fn is_null(&self) -> bool {
+        false
+    }
+
+    fn as_seq(&self) -> Option<&[Self]> {
+        self.as_array().map(Vec::as_slice)
+    }
+
+    fn into_seq(self) -> Option<Vec<Self>> {
+        match self {
+            Self::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    fn as_map(&self) -> Option<Vec<(&str, &Self)>> {
+        self.as_table().map(|table| {
+            table
+                .iter()
+                .map(|(key, value)| (key.as_str(), value))
+                .collect()
+        })
+    }
+
+    fn into_map(self) -> Option<Vec<(String, Self)>> {
+        match self {
+            Self::Table(table) => Some(table.into_iter().collect()),
+            _ => None,
+        }
+    }
+}
+
+crate::decoded!(Value);
+
+/// Blanket implementation of a convenience method `try_cast_from_toml` which parses a TOML
+/// document and casts the resulting [`Value`] in one call. Do not implement this trait.
+///
+/// To parse a primitive, a string, a `Vec`, or a map, cast into a [`Decoded`](crate::Decoded)
+/// wrapper; TOML has no null, so a `Decoded<Option<T>>` is always `Some`. Example:
+/// ```
+/// use safecast::{TomlCastError, TryCastFrom, TryCastFromToml};
+/// use toml::Value;
+///
+/// #[derive(Debug)]
+/// struct Port(u16);
+///
+/// impl TryCastFrom<Value> for Port {
+///     fn can_cast_from(value: &Value) -> bool {
+///         match value.get("port").and_then(Value::as_integer) {
+///             Some(port) => u16::try_from(port).is_ok(),
+///             None => false,
+///         }
+///     }
+///
+///     fn opt_cast_from(value: Value) -> Option<Self> {
+///         let port = value.get("port")?.as_integer()?;
+///         u16::try_from(port).ok().map(Port)
+///     }
+/// }
+///
+/// assert_eq!(Port::try_cast_from_toml("port = 8080").unwrap().0, 8080);
+/// assert!(matches!(Port::try_cast_from_toml("port ="), Err(TomlCastError::Parse(_))));
+/// assert!(matches!(Port::try_cast_from_toml("port = -1"), Err(TomlCastError::Cast(_))));
+///
+/// type Hosts = safecast::Decoded<std::collections::BTreeMap<String, Vec<String>>>;
+/// let hosts = Hosts::try_cast_from_toml(r#"web = ["a", "b"]"#).unwrap();
+/// assert_eq!(hosts["web"], ["a", "b"]);
+/// ```
+pub trait TryCastFromToml: TryCastFrom<Value> {
+    /// Parse the TOML document `toml` and cast the resulting [`Value`] into `Self`.
+    fn try_cast_from_toml(toml: &str) -> Result<Self, TomlCastError> {
+        cast_parsed(::toml::from_str(toml))
+    }
+}
+
+impl<T: TryCastFrom<Value>> TryCastFromToml for T {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::Decoded;
+
+    #[test]
+    fn test_try_cast_from_toml() {
+        let value = Value::try_cast_from_toml("a = [1, 2]").unwrap();
+        assert_eq!(value["a"][1].as_integer(), Some(2));
+
+        let err = Value::try_cast_from_toml("a = [").unwrap_err();
+        assert!(err.to_string().starts_with("invalid TOML"));
+    }
+
+    #[test]
+    fn test_decoded_toml() {
+        type Config = Decoded<HashMap<String, Vec<u16>>>;
+
+        let config = Config::try_cast_from_toml("ports = [80, 443]").unwrap();
+        assert_eq!(config["ports"], [80, 443]);
+
+        let err = Config::try_cast_from_toml("ports = [80, -1]").unwrap_err();
+        assert!(matches!(err, TomlCastError::Cast(_)));
+
+        let table = Value::try_cast_from_toml("a = 0.5").unwrap();
+        assert_eq!(
+            Decoded::<f32>::opt_cast_from(table["a"].clone()),
+            Some(Decoded(0.5))
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Casting between [`Url`] and strings, using [`url`](::url).
+
+use ::url::Url;
+
+use super::Parsed;
+
+impl From<Parsed<Url>> for String {
+    fn from(url: Parsed<Url>) -> Self {
+        url.0.into()
+    }
+}
+
+impl From<Parsed<Url>> for Url {
+    fn from(url: Parsed<Url>) -> Self {
+        url.0
+    }
+}
+
+impl AsRef<str> for Parsed<Url> {
+    fn as_ref(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CastInto, Match, TryCastFrom};
+
+    #[test]
+    fn test_url() {
+        assert!(Parsed::<Url>::can_cast_from(&"https://tinychain.net/"));
+        assert!(!Parsed::<Url>::can_cast_from(&"tinychain.net"));
+
+        let url = Parsed::<Url>::opt_cast_from("https://tinychain.net/".to_string()).unwrap();
+        assert_eq!(url.host_str(), Some("tinychain.net"));
+        assert_eq!(url.as_ref(), "https://tinychain.net/");
+
+        let url: String = url.cast_into();
+        assert_eq!(url, "https://tinychain.net/");
+        assert!(url.matches::<Parsed<Url>>());
+    }
+}
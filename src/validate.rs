@@ -0,0 +1,356 @@
+//! A [`Validated`] wrapper which enforces a [`Predicate`] when cast into.
+
+#[cfg(feature = "allocator_api")]
+use std::alloc::Allocator;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use super::TryCastFrom;
+
+/// A condition which an instance of `T` must satisfy in order to be cast into a [`Validated`].
+pub trait Predicate<T: ?Sized> {
+    /// Returns `true` if `value` satisfies this predicate.
+    fn test(value: &T) -> bool;
+}
+
+/// A [`Predicate`] which requires a string or sequence to contain at least one element.
+pub struct NonEmpty;
+
+impl Predicate<String> for NonEmpty {
+    fn test(value: &String) -> bool {
+        !value.is_empty()
+    }
+}
+
+impl<'a> Predicate<&'a str> for NonEmpty {
+    fn test(value: &&'a str) -> bool {
+        !value.is_empty()
+    }
+}
+
+#[cfg(not(feature = "allocator_api"))]
+impl<T> Predicate<Vec<T>> for NonEmpty {
+    fn test(value: &Vec<T>) -> bool {
+        !value.is_empty()
+    }
+}
+
+#[cfg(feature = "allocator_api")]
+impl<T, A: Allocator> Predicate<Vec<T, A>> for NonEmpty {
+    fn test(value: &Vec<T, A>) -> bool {
+        !value.is_empty()
+    }
+}
+
+impl<'a, T> Predicate<&'a [T]> for NonEmpty {
+    fn test(value: &&'a [T]) -> bool {
+        !value.is_empty()
+    }
+}
+
+/// An instance of `T` which is known to satisfy the [`Predicate`] `P`.
+///
+/// A `Validated` can only be constructed by casting, so holding one is proof that the predicate
+/// was checked. Example:
+/// ```
+/// use safecast::{Predicate, TryCastFrom, Validated};
+///
+/// struct Port;
+///
+/// impl Predicate<u16> for Port {
+///     fn test(port: &u16) -> bool {
+///         *port >= 1024
+///     }
+/// }
+///
+/// assert!(Validated::<u16, Port>::can_cast_from(&8080));
+/// assert!(Validated::<u16, Port>::opt_cast_from(80).is_none());
+/// ```
+///
+/// Because the blanket implementation of [`TryCastFrom`] covers every type which implements
+/// [`From`], `Validated<T, P>` can only implement `TryCastFrom<T>` for concrete types `T`.
+/// This crate provides implementations for the primitive types, `String`, `&str`, `Vec<T>`
+/// (with any allocator, given the `allocator_api` feature), and `&[T]`; use the
+/// [`validated!`](crate::validated) macro to provide one for your own type.
+///
+/// To validate a value of some other type which casts into `T`, use [`Validated::opt_cast_via`]
+/// (or [`Validated::can_cast_via`] and [`Validated::try_cast_via`]).
+pub struct Validated<T, P> {
+    value: T,
+    predicate: PhantomData<P>,
+}
+
+impl<T, P: Predicate<T>> Validated<T, P> {
+    /// Returns `true` if `value` can be cast into `T` and the result satisfies `P`.
+    ///
+    /// The predicate can only be tested on an instance of `T`, so this casts a clone of `value`.
+    pub fn can_cast_via<F: Clone>(value: &F) -> bool
+    where
+        T: TryCastFrom<F>,
+    {
+        T::can_cast_from(value) && Self::opt_cast_via(value.clone()).is_some()
+    }
+
+    /// Cast `value` into `T` and then validate it, if possible.
+    pub fn opt_cast_via<F>(value: F) -> Option<Self>
+    where
+        T: TryCastFrom<F>,
+    {
+        T::opt_cast_from(value)
+            .filter(P::test)
+            .map(Self::new_unchecked)
+    }
+
+    /// Cast `value` into `T` and then validate it, or call `on_err` if either step fails.
+    ///
+    /// Like [`Validated::can_cast_via`], this casts a clone of `value` so that `on_err` can still
+    /// borrow it; use [`Validated::opt_cast_via`] if `F` does not implement [`Clone`].
+    pub fn try_cast_via<F: Clone, Err, OnErr: FnOnce(&F) -> Err>(
+        value: F,
+        on_err: OnErr,
+    ) -> Result<Self, Err>
+    where
+        T: TryCastFrom<F>,
+    {
+        if T::can_cast_from(&value) {
+            Self::opt_cast_via(value.clone()).ok_or_else(|| on_err(&value))
+        } else {
+            Err(on_err(&value))
+        }
+    }
+}
+
+impl<T, P> Validated<T, P> {
+    pub(crate) fn new_unchecked(value: T) -> Self {
+        Self {
+            value,
+            predicate: PhantomData,
+        }
+    }
+
+    /// Return the validated value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, P> AsRef<T> for Validated<T, P> {
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, P> Deref for Validated<T, P> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Clone, P> Clone for Validated<T, P> {
+    fn clone(&self) -> Self {
+        Self::new_unchecked(self.value.clone())
+    }
+}
+
+impl<T: Copy, P> Copy for Validated<T, P> {}
+
+impl<T: PartialEq, P> PartialEq for Validated<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq, P> Eq for Validated<T, P> {}
+
+impl<T: PartialOrd, P> PartialOrd for Validated<T, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Ord, P> Ord for Validated<T, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<T: Hash, P> Hash for Validated<T, P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state)
+    }
+}
+
+impl<T: fmt::Debug, P> fmt::Debug for Validated<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.value, f)
+    }
+}
+
+impl<T: fmt::Display, P> fmt::Display for Validated<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.value, f)
+    }
+}
+
+/// Validate `value`, for the implementations generated by [`validated!`](crate::validated).
+pub fn validate<T, P: Predicate<T>>(value: T) -> Option<Validated<T, P>> {
+    if P::test(&value) {
+        Some(Validated::new_unchecked(value))
+    } else {
+        None
+    }
+}
+
+/// Implement `TryCastFrom<T>` for [`Validated<T, P>`](crate::Validated) for a concrete type `T`.
+/// Example:
+/// ```
+/// use safecast::{validated, Predicate, TryCastFrom, Validated};
+///
+/// struct Email(String);
+///
+/// struct HasAt;
+///
+/// impl Predicate<Email> for HasAt {
+///     fn test(email: &Email) -> bool {
+///         email.0.contains('@')
+///     }
+/// }
+///
+/// validated!(Email);
+///
+/// let email = Email("code@tinychain.net".to_string());
+/// assert!(Validated::<Email, HasAt>::opt_cast_from(email).is_some());
+/// ```
+#[macro_export]
+macro_rules! validated {
+    ($t:ident<$($tg:tt),*>) => {
+        impl<$($tg),*, P: $crate::Predicate<$t<$($tg),*>>> $crate::TryCastFrom<$t<$($tg),*>>
+            for $crate::Validated<$t<$($tg),*>, P>
+        {
+            fn can_cast_from(value: &$t<$($tg),*>) -> bool {
+                P::test(value)
+            }
+
+            fn opt_cast_from(value: $t<$($tg),*>) -> Option<Self> {
+                $crate::__private::validate(value)
+            }
+        }
+    };
+    ($t:ty; $($lt:lifetime),*) => {
+        impl<$($lt,)* P: $crate::Predicate<$t>> $crate::TryCastFrom<$t>
+            for $crate::Validated<$t, P>
+        {
+            fn can_cast_from(value: &$t) -> bool {
+                P::test(value)
+            }
+
+            fn opt_cast_from(value: $t) -> Option<Self> {
+                $crate::__private::validate(value)
+            }
+        }
+    };
+    ($t:ty) => {
+        $crate::validated!($t;);
+    };
+}
+
+validated!(bool);
+validated!(char);
+validated!(u8);
+validated!(u16);
+validated!(u32);
+validated!(u64);
+validated!(u128);
+validated!(usize);
+validated!(i8);
+validated!(i16);
+validated!(i32);
+validated!(i64);
+validated!(i128);
+validated!(isize);
+validated!(f32);
+validated!(f64);
+validated!(String);
+#[cfg(not(feature = "allocator_api"))]
+validated!(Vec<T>);
+validated!(&'a str; 'a);
+
+#[cfg(feature = "allocator_api")]
+impl<T, A: Allocator, P: Predicate<Vec<T, A>>> TryCastFrom<Vec<T, A>> for Validated<Vec<T, A>, P> {
+    fn can_cast_from(value: &Vec<T, A>) -> bool {
+        P::test(value)
+    }
+
+    fn opt_cast_from(value: Vec<T, A>) -> Option<Self> {
+        validate(value)
+    }
+}
+
+impl<'a, T, P: Predicate<&'a [T]>> TryCastFrom<&'a [T]> for Validated<&'a [T], P> {
+    fn can_cast_from(value: &&'a [T]) -> bool {
+        P::test(value)
+    }
+
+    fn opt_cast_from(value: &'a [T]) -> Option<Self> {
+        validate(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Port;
+
+    impl Predicate<u16> for Port {
+        fn test(port: &u16) -> bool {
+            *port >= 1024
+        }
+    }
+
+    #[test]
+    fn test_validated() {
+        assert!(Validated::<u16, Port>::can_cast_from(&8080));
+        assert!(!Validated::<u16, Port>::can_cast_from(&80));
+        assert_eq!(*Validated::<u16, Port>::opt_cast_from(8080).unwrap(), 8080);
+        assert!(Validated::<u16, Port>::opt_cast_from(80).is_none());
+
+        assert!(Validated::<String, NonEmpty>::opt_cast_from(String::new()).is_none());
+        assert!(Validated::<&str, NonEmpty>::opt_cast_from("a").is_some());
+        assert!(Validated::<&[u8], NonEmpty>::opt_cast_from(&[][..]).is_none());
+    }
+
+    #[test]
+    fn test_validated_via() {
+        assert!(Validated::<String, NonEmpty>::opt_cast_via("a").is_some());
+        assert!(Validated::<String, NonEmpty>::opt_cast_via("").is_none());
+
+        assert!(Validated::<String, NonEmpty>::can_cast_via(&"a"));
+        assert!(!Validated::<String, NonEmpty>::can_cast_via(&""));
+
+        let port = Validated::<u16, Port>::try_cast_via(8080u16, |n| *n);
+        assert_eq!(port.map(Validated::into_inner), Ok(8080));
+        assert_eq!(Validated::<u16, Port>::try_cast_via(80u8, |n| *n), Err(80));
+
+        let name = Validated::<String, NonEmpty>::try_cast_via("", |s| s.to_string());
+        assert_eq!(name, Err(String::new()));
+    }
+
+    #[cfg(feature = "allocator_api")]
+    #[test]
+    fn test_validated_allocator() {
+        let mut items = Vec::new_in(std::alloc::System);
+        items.push(1u8);
+
+        let items: Vec<u8, std::alloc::System> = Validated::<_, NonEmpty>::opt_cast_from(items)
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(items, [1]);
+    }
+}
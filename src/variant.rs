@@ -0,0 +1,97 @@
+//! The [`VariantName`] trait to describe which variant of an enum a value is.
+
+/// An enum which can report the name of the variant of each of its instances.
+///
+/// Implement this with the [`variant_name!`](crate::variant_name) macro, or with
+/// `#[derive(VariantName)]` given the `derive` feature, and use it to describe a failed cast with
+/// [`CastError::of_variant`](crate::CastError::of_variant).
+pub trait VariantName {
+    /// The name of the variant of this instance.
+    fn variant_name(&self) -> &'static str;
+}
+
+/// Implement [`VariantName`] for an enum, given the names of all of its variants.
+/// Example:
+/// ```
+/// use std::collections::HashMap;
+/// use safecast::{variant_name, CastError, TryCastFrom, VariantName};
+///
+/// enum Value {
+///     Int(u64),
+///     Map(HashMap<String, Value>),
+///     Nil,
+/// }
+///
+/// variant_name!(Value, Int, Map, Nil);
+///
+/// impl TryCastFrom<Value> for u64 {
+///     fn can_cast_from(value: &Value) -> bool {
+///         matches!(value, Value::Int(_))
+///     }
+///
+///     fn opt_cast_from(value: Value) -> Option<Self> {
+///         match value {
+///             Value::Int(n) => Some(n),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// let map = Value::Map(HashMap::new());
+/// assert_eq!(map.variant_name(), "Map");
+///
+/// let err = u64::try_cast_from(map, CastError::of_variant::<_, u64>).unwrap_err();
+/// assert!(err.to_string().ends_with("Value::Map into u64"));
+/// ```
+#[macro_export]
+macro_rules! variant_name {
+    ($c:ident<$($cg:tt),*>, $($variant:ident),+) => {
+        impl<$($cg),+> $crate::VariantName for $c<$($cg),+> {
+            fn variant_name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant { .. } => stringify!($variant),)+
+                }
+            }
+        }
+    };
+    ($c:ty, $($variant:ident),+) => {
+        impl $crate::VariantName for $c {
+            fn variant_name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant { .. } => stringify!($variant),)+
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CastError, VariantName};
+
+    #[allow(dead_code)]
+    enum Shape<T> {
+        Point,
+        Circle(T),
+        Rect { width: T, height: T },
+    }
+
+    variant_name!(Shape<T>, Point, Circle, Rect);
+
+    #[test]
+    fn test_variant_name() {
+        assert_eq!(Shape::<u8>::Point.variant_name(), "Point");
+        assert_eq!(Shape::Circle(1).variant_name(), "Circle");
+
+        let rect = Shape::Rect {
+            width: 1,
+            height: 2,
+        };
+        assert_eq!(rect.variant_name(), "Rect");
+
+        let err = CastError::of_variant::<_, u64>(&rect);
+        assert_eq!(err.source_variant(), Some("Rect"));
+        assert!(err.to_string().ends_with("Shape<i32>::Rect into u64"));
+        assert_eq!(CastError::new::<u8, u64>().source_variant(), None);
+    }
+}
@@ -0,0 +1,144 @@
+//! Casting from YAML documents, using [`serde_yaml`].
+
+use serde_yaml::Value;
+
+use super::document::cast_parsed;
+use super::{Document, FormatCastError, ParseError, TryCastFrom};
+
+/// An error which occurs when a YAML document cannot be cast into a target type.
+pub type YamlCastError = FormatCastError<serde_yaml::Error>;
+
+impl ParseError for serde_yaml::Error {
+    const FORMAT: &'static str = "YAML";
+}
+
+impl Document for Value {
+    fn as_bool(&self) -> Option<bool> {
+        Value::as_bool(self)
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        Value::as_i64(self)
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        Value::as_u64(self)
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        Value::as_f64(self)
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        Value::as_str(self)
+    }
+
+    fn is_null(&self) -> bool {
+        Value::is_null(self)
+    }
+
+    fn as_seq(&self) -> Option<&[Self]> {
+        self.as_sequence().map(Vec::as_slice)
+    }
+
+    fn into_seq(self) -> Option<Vec<Self>> {
+        match self {
+            Self::Sequence(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    fn as_map(&self) -> Option<Vec<(&str, &Self)>> {
+        self.as_mapping()?
+            .iter()
+            .map(|(key, value)| Some((key.as_str()?, value)))
+            .collect()
+    }
+
+    fn into_map(self) -> Option<Vec<(String, Self)>> {
+        match self {
+            Self::Mapping(map) => map
+                .into_iter()
+                .map(|(key, value)| match key {
+                    Self::String(key) => Some((key, value)),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+crate::decoded!(Value);
+
+/// Blanket implementation of a convenience method `try_cast_from_yaml` which parses a YAML
+/// document and casts the resulting [`Value`] in one call. Do not implement this trait.
+///
+/// To parse a primitive, a string, a `Vec`, or a map whose keys are all strings, cast into a
+/// [`Decoded`](crate::Decoded) wrapper. Example:
+/// ```
+/// use safecast::{TryCastFrom, TryCastFromYaml, YamlCastError};
+/// use serde_yaml::Value;
+///
+/// #[derive(Debug)]
+/// struct Hosts(Vec<String>);
+///
+/// impl TryCastFrom<Value> for Hosts {
+///     fn can_cast_from(value: &Value) -> bool {
+///         match value.as_sequence() {
+///             Some(hosts) => hosts.iter().all(Value::is_string),
+///             None => false,
+///         }
+///     }
+///
+///     fn opt_cast_from(value: Value) -> Option<Self> {
+///         let hosts = value.as_sequence()?;
+///         let hosts = hosts.iter().map(|host| host.as_str().map(String::from));
+///         hosts.collect::<Option<Vec<String>>>().map(Hosts)
+///     }
+/// }
+///
+/// assert_eq!(Hosts::try_cast_from_yaml("[a, b]").unwrap().0, ["a", "b"]);
+/// assert!(matches!(Hosts::try_cast_from_yaml("[a"), Err(YamlCastError::Parse(_))));
+/// assert!(matches!(Hosts::try_cast_from_yaml("a: b"), Err(YamlCastError::Cast(_))));
+///
+/// let ports = safecast::Decoded::<Vec<u16>>::try_cast_from_yaml("[80, 443]").unwrap();
+/// assert_eq!(ports.into_inner(), [80, 443]);
+/// ```
+pub trait TryCastFromYaml: TryCastFrom<Value> {
+    /// Parse the YAML document `yaml` and cast the resulting [`Value`] into `Self`.
+    fn try_cast_from_yaml(yaml: &str) -> Result<Self, YamlCastError> {
+        cast_parsed(serde_yaml::from_str(yaml))
+    }
+}
+
+impl<T: TryCastFrom<Value>> TryCastFromYaml for T {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::Decoded;
+
+    #[test]
+    fn test_try_cast_from_yaml() {
+        let value = Value::try_cast_from_yaml("a: [1, 2]").unwrap();
+        assert_eq!(value["a"][1], 2);
+
+        let err = Value::try_cast_from_yaml("a: [").unwrap_err();
+        assert!(err.to_string().starts_with("invalid YAML"));
+    }
+
+    #[test]
+    fn test_decoded_yaml() {
+        type Limits = Decoded<BTreeMap<String, Option<u64>>>;
+
+        let limits = Limits::try_cast_from_yaml("a: 1\nb: ~").unwrap();
+        assert_eq!(limits["a"], Some(1));
+        assert_eq!(limits["b"], None);
+
+        assert!(Limits::try_cast_from_yaml("1: 1").is_err());
+        assert!(Limits::try_cast_from_yaml("a: -1").is_err());
+    }
+}